@@ -0,0 +1,458 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use ml_library::{activation::ActivationFunction, conv_params::PaddingType, layer::Layer, loss_function::LossType, network::Network};
+use serde::Deserialize;
+
+use crate::dataset::{Augmentation, ColorMode, Column, ConvDataset, ConvSample, CoordinateImage, Dataset, DenseDataset, Idx, ImageFolder, LabelSource, Pipeline, Sampling, Step, Tabular};
+use crate::error::GuiError;
+use crate::export::ExportOptions;
+use crate::layout::Layout;
+use crate::session::TrainingSession;
+use crate::widget::WidgetView;
+use crate::widgets::*;
+
+/// Experiment description loaded by `GUI::from_config`, written in TOML or JSON.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Config {
+    #[serde(default)]
+    pub training: TrainingConfig,
+    pub network: Option<NetworkConfig>,
+    #[serde(default)]
+    pub data: DataConfig,
+    /// Separate held out data, takes precedence over `training.validation_split`.
+    pub validation: Option<DataConfig>,
+    /// Steps applied to every input, e.g. `[[preprocessing]] step = "standardize"`.
+    #[serde(default)]
+    pub preprocessing: Vec<Step>,
+    /// Random distortions of conv samples during training, e.g. `[augmentation] rotation = 10.0`.
+    pub augmentation: Option<Augmentation>,
+    /// Super-resolution render of coordinate networks, e.g. `[export] width = 1024` with `colormap = "viridis"`.
+    #[serde(default)]
+    pub export: ExportOptions,
+    pub layout: LayoutConfig
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TrainingConfig {
+    pub epochs_per_second: usize,
+    pub model_path: String,
+    /// Start from the model saved at `model_path` instead of a freshly initialised network.
+    pub load_model: bool,
+    pub paused: bool,
+    /// Fraction of `data` held out for validation.
+    pub validation_split: Option<f64>,
+    /// Evaluate the validation set every this many epochs.
+    pub validation_interval: usize,
+    /// Compute the outputs the output and evaluation widgets show every this many epochs.
+    pub output_interval: usize,
+    /// e.g. `sampling = { mode = "balanced", size = 32 }`.
+    pub sampling: Sampling,
+    /// Seed for shuffling and sampling, taken from the clock when missing.
+    pub seed: Option<u64>,
+    /// Loss a loaded model was trained with, needed when there is no `[network]` section.
+    pub loss: Option<String>
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        TrainingConfig {
+            epochs_per_second: 1,
+            model_path: "Model".to_string(),
+            load_model: false,
+            paused: false,
+            validation_split: None,
+            validation_interval: 1,
+            output_interval: 1,
+            sampling: Sampling::Sequential,
+            seed: None,
+            loss: None
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkConfig {
+    pub layers: Vec<LayerConfig>,
+    pub learning_rate: f64,
+    pub batch_size: usize,
+    pub loss: String
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LayerConfig {
+    Dense { inputs: usize, outputs: usize, activation: String },
+    Conv { kernel: usize, padding: String, stride: usize, activation: String },
+    Pool { size: usize, stride: usize }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum DataConfig {
+    #[default]
+    None,
+    Dense { samples: Vec<[Vec<f64>; 2]> },
+    Conv { samples: Vec<ConvSample> },
+    ImageFolder {
+        path: String,
+        /// File stem pattern such as `mnist_{}`, labels come from subfolders when missing.
+        pattern: Option<String>,
+        #[serde(default = "default_color")]
+        color: ColorMode,
+        /// Flatten images into dense samples instead of conv samples.
+        #[serde(default)]
+        flatten: bool
+    },
+    Csv {
+        path: String,
+        #[serde(flatten)]
+        table: TableConfig
+    },
+    Json {
+        path: String,
+        #[serde(flatten)]
+        table: TableConfig
+    },
+    /// Every pixel of one image as an `(x, y)` to intensity sample.
+    CoordinateImage {
+        path: String,
+        #[serde(default = "default_color")]
+        color: ColorMode
+    },
+    /// MNIST style IDX files, plain or gzip-compressed.
+    Idx {
+        images: String,
+        labels: String,
+        limit: Option<usize>,
+        /// Width of the one-hot targets, 10 by default.
+        classes: Option<usize>,
+        #[serde(default)]
+        flatten: bool
+    }
+}
+
+/// Columns of a CSV or JSON table, by header name or index.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TableConfig {
+    pub inputs: Vec<Column>,
+    pub targets: Vec<Column>,
+    #[serde(default)]
+    pub categorical: Vec<Column>,
+    #[serde(default = "default_header")]
+    pub header: bool,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LayoutConfig {
+    Widget { widget: WidgetConfig },
+    Horizontal { children: Vec<ChildConfig> },
+    Vertical { children: Vec<ChildConfig> },
+    Grid { rows: Vec<f64>, columns: Vec<f64>, cells: Vec<CellConfig> }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChildConfig {
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    #[serde(flatten)]
+    pub layout: LayoutConfig
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CellConfig {
+    pub row: usize,
+    pub column: usize,
+    #[serde(default = "default_span")]
+    pub row_span: usize,
+    #[serde(default = "default_span")]
+    pub column_span: usize,
+    #[serde(flatten)]
+    pub layout: LayoutConfig
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum WidgetConfig {
+    CostPlot {
+        /// Start in the rolling view of the last this many epochs, `Backspace` toggles it.
+        #[serde(alias = "expiration")]
+        window: Option<usize>,
+        /// CSV file the full cost history is written to.
+        log: Option<String>,
+        /// Start with a log scale y-axis, `G` toggles it.
+        #[serde(default)]
+        log_scale: bool,
+        /// Weight of the previous value in the moving average overlay, `0.0` hides it.
+        smoothing: Option<f64>
+    },
+    Architecture,
+    OutputImg,
+    OutputGraph {
+        /// Fixed `[min, max]` of the x-axis instead of the data's input range.
+        x_range: Option<[f64; 2]>,
+        /// Fixed `[min, max]` of the y-axis instead of fitting targets and outputs.
+        y_range: Option<[f64; 2]>
+    },
+    ConvArch,
+    AugmentPreview,
+    Accuracy,
+    ConfusionMatrix {
+        /// Start with rows normalised instead of counts, `N` toggles it.
+        #[serde(default)]
+        normalized: bool
+    },
+    PredictionGallery {
+        /// `training` (default) or `validation` samples.
+        source: Option<GallerySource>,
+        per_page: Option<usize>,
+        /// Start sorted by most confidently wrong, `W` toggles it.
+        #[serde(default)]
+        wrong_first: bool
+    }
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+fn default_span() -> usize {
+    1
+}
+
+fn default_color() -> ColorMode {
+    ColorMode::Grayscale
+}
+
+fn default_header() -> bool {
+    true
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+impl Config {
+    /// Parses a `.json` file as JSON and anything else as TOML.
+    pub fn load(path: &str) -> Result<Self, GuiError> {
+        let text = fs::read_to_string(path).map_err(|e| GuiError::io(path, e))?;
+        let is_json = Path::new(path).extension().is_some_and(|ext| ext == "json");
+        if is_json {
+            serde_json::from_str(&text).map_err(|e| GuiError::Config(format!("{}: {}", path, e)))
+        } else {
+            toml::from_str(&text).map_err(|e| GuiError::Config(format!("{}: {}", path, e)))
+        }
+    }
+
+    pub fn build_session(&self) -> Result<TrainingSession, GuiError> {
+        let loss = match (&self.network, &self.training.loss) {
+            (Some(network), _) => parse_loss(&network.loss)?,
+            (None, Some(loss)) => parse_loss(loss)?,
+            (None, None) => return Err(GuiError::Config("training.loss is required when loading a model without a [network] section".to_string())),
+        };
+        let nn = if self.training.load_model {
+            TrainingSession::check_model(&self.training.model_path)?;
+            Network::from_load(&self.training.model_path)
+        } else {
+            match &self.network {
+                Some(network) => network.build()?,
+                None => return Err(GuiError::Config("a [network] section is required unless training.load_model is set".to_string())),
+            }
+        };
+
+        let mut session = TrainingSession::new(nn, loss);
+        session.set_epochs_per_second(self.training.epochs_per_second);
+        session.set_model_name(&self.training.model_path);
+        session.will_train = !self.training.paused;
+        session.set_validation_interval(self.training.validation_interval);
+        session.set_output_interval(self.training.output_interval);
+        session.set_sampling(self.training.sampling, self.training.seed);
+
+        if let Some(dataset) = self.data.build()? {
+            session.set_dataset(dataset);
+        }
+        match (&self.validation, self.training.validation_split) {
+            (Some(validation), _) => if let Some(dataset) = validation.build()? {
+                session.set_validation(dataset);
+            },
+            (None, Some(fraction)) => session.split_validation(fraction),
+            (None, None) => (),
+        }
+
+        if !self.preprocessing.is_empty() {
+            session.set_preprocessing(Pipeline::new(self.preprocessing.clone()));
+        }
+        if self.training.load_model {
+            session.load_preprocessing()?;
+        }
+        if let Some(augmentation) = self.augmentation {
+            session.set_augmentation(augmentation);
+        }
+        session.set_export(self.export.clone());
+        Ok(session)
+    }
+}
+
+impl DataConfig {
+    /// Loads the samples, `None` when no data source is configured.
+    pub fn build(&self) -> Result<Option<Arc<dyn Dataset>>, GuiError> {
+        let dataset: Arc<dyn Dataset> = match self {
+            DataConfig::None => return Ok(None),
+            DataConfig::Dense { samples } => Arc::new(DenseDataset::new(samples.clone())),
+            DataConfig::Conv { samples } => Arc::new(ConvDataset::new(samples.clone())),
+            DataConfig::ImageFolder { path, pattern, color, flatten } => {
+                let labels = match pattern {
+                    Some(pattern) => LabelSource::Filename(pattern.clone()),
+                    None => LabelSource::Subfolder,
+                };
+                let folder = ImageFolder::new(path).with_labels(labels).with_color(*color);
+                if *flatten {
+                    Arc::new(folder.load_dense()?)
+                } else {
+                    Arc::new(folder.load_conv()?)
+                }
+            },
+            DataConfig::CoordinateImage { path, color } => Arc::new(CoordinateImage::load(path, *color)?),
+            DataConfig::Csv { path, table } => Arc::new(table.build().load_csv(path)?),
+            DataConfig::Json { path, table } => Arc::new(table.build().load_json(path)?),
+            DataConfig::Idx { images, labels, limit, classes, flatten } => {
+                let mut idx = Idx::new(images, labels);
+                if let Some(limit) = limit {
+                    idx = idx.with_limit(*limit);
+                }
+                if let Some(classes) = classes {
+                    idx = idx.with_classes(*classes);
+                }
+                if *flatten {
+                    Arc::new(idx.load_dense()?)
+                } else {
+                    Arc::new(idx.load_conv()?)
+                }
+            },
+        };
+        Ok(Some(dataset))
+    }
+}
+
+impl NetworkConfig {
+    pub fn build(&self) -> Result<Network, GuiError> {
+        let mut layers = vec![];
+        for layer in &self.layers {
+            layers.push(match layer {
+                LayerConfig::Dense { inputs, outputs, activation } =>
+                    Layer::dense([*inputs, *outputs], parse_activation(activation)?),
+                LayerConfig::Conv { kernel, padding, stride, activation } =>
+                    Layer::conv(*kernel, parse_padding(padding)?, *stride, parse_activation(activation)?),
+                LayerConfig::Pool { size, stride } =>
+                    Layer::pool(*size, *stride),
+            });
+        }
+        Ok(Network::new(layers, self.learning_rate, self.batch_size, parse_loss(&self.loss)?))
+    }
+}
+
+impl TableConfig {
+    pub fn build(&self) -> Tabular {
+        Tabular::new(self.inputs.clone(), self.targets.clone())
+            .with_categorical(self.categorical.clone())
+            .with_header(self.header)
+            .with_delimiter(self.delimiter as u8)
+    }
+}
+
+impl LayoutConfig {
+    pub fn build(&self) -> Layout<Box<dyn WidgetView>> {
+        match self {
+            LayoutConfig::Widget { widget } => Layout::leaf(widget.build()),
+            LayoutConfig::Horizontal { children } =>
+                Layout::horizontal(children.iter().map(|child| (child.weight, child.layout.build())).collect()),
+            LayoutConfig::Vertical { children } =>
+                Layout::vertical(children.iter().map(|child| (child.weight, child.layout.build())).collect()),
+            LayoutConfig::Grid { rows, columns, cells } => {
+                let mut grid = Layout::grid(rows.clone(), columns.clone());
+                for cell in cells {
+                    grid = grid.cell(cell.row, cell.column, cell.row_span, cell.column_span, cell.layout.build());
+                }
+                grid
+            },
+        }
+    }
+}
+
+impl WidgetConfig {
+    pub fn build(&self) -> Box<dyn WidgetView> {
+        match self {
+            WidgetConfig::CostPlot { window, log, log_scale, smoothing } => {
+                let mut plot = CostPlot::new().with_log_scale(*log_scale);
+                if let Some(epochs) = window {
+                    plot = plot.with_window(*epochs);
+                }
+                if let Some(path) = log {
+                    plot = plot.with_log(path);
+                }
+                if let Some(smoothing) = smoothing {
+                    plot = plot.with_smoothing(*smoothing);
+                }
+                Box::new(plot)
+            },
+            WidgetConfig::Architecture => Box::new(Architecture::new()),
+            WidgetConfig::OutputImg => Box::new(OutputImg::new()),
+            WidgetConfig::OutputGraph { x_range, y_range } => {
+                let mut graph = OutputGraph::new();
+                if let Some([min, max]) = x_range {
+                    graph = graph.with_x_range(*min, *max);
+                }
+                if let Some([min, max]) = y_range {
+                    graph = graph.with_y_range(*min, *max);
+                }
+                Box::new(graph)
+            },
+            WidgetConfig::ConvArch => Box::new(ConvArch::new()),
+            WidgetConfig::AugmentPreview => Box::new(AugmentPreview::new()),
+            WidgetConfig::Accuracy => Box::new(Accuracy::new()),
+            WidgetConfig::ConfusionMatrix { normalized } =>
+                Box::new(ConfusionMatrix::new().with_normalized(*normalized)),
+            WidgetConfig::PredictionGallery { source, per_page, wrong_first } => {
+                let mut gallery = PredictionGallery::new().with_wrong_first(*wrong_first);
+                if let Some(source) = source {
+                    gallery = gallery.with_source(*source);
+                }
+                if let Some(samples) = per_page {
+                    gallery = gallery.with_per_page(*samples);
+                }
+                Box::new(gallery)
+            },
+        }
+    }
+}
+
+fn parse_activation(name: &str) -> Result<ActivationFunction, GuiError> {
+    match name.to_lowercase().as_str() {
+        "sigmoid" => Ok(ActivationFunction::Sigmoid),
+        "relu" => Ok(ActivationFunction::ReLU),
+        "tanh" => Ok(ActivationFunction::TanH),
+        "softmax" => Ok(ActivationFunction::SoftMax),
+        _ => Err(GuiError::Config(format!("Unknown activation function: {}", name))),
+    }
+}
+
+fn parse_padding(name: &str) -> Result<PaddingType, GuiError> {
+    match name.to_lowercase().as_str() {
+        "valid" => Ok(PaddingType::Valid),
+        "same" => Ok(PaddingType::Same),
+        _ => Err(GuiError::Config(format!("Unknown padding type: {}", name))),
+    }
+}
+
+fn parse_loss(name: &str) -> Result<LossType, GuiError> {
+    match name.to_lowercase().as_str() {
+        "mse" => Ok(LossType::MSE),
+        "cel" => Ok(LossType::CEL),
+        _ => Err(GuiError::Config(format!("Unknown loss function: {}", name))),
+    }
+}
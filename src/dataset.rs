@@ -0,0 +1,325 @@
+pub mod augment;
+pub mod coordinate;
+pub mod function;
+pub mod idx;
+pub mod image_folder;
+pub mod preprocess;
+pub mod sampling;
+pub mod tabular;
+
+pub use augment::{Augmentation, Augmenter};
+pub use coordinate::CoordinateImage;
+pub use function::{Density, Function, FunctionDataset};
+pub use idx::Idx;
+pub use image_folder::{ColorMode, ImageFolder, LabelSource};
+pub use preprocess::{Pipeline, Preprocessed, Step};
+pub use sampling::{Sampler, Sampling};
+pub use tabular::{Column, Tabular};
+
+use std::sync::Arc;
+
+/// Input of a single sample, either a flat vector for dense networks or `[channel][row][column]` for conv networks.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    Dense(Vec<f64>),
+    Conv(Vec<Vec<Vec<f64>>>)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub input: Input,
+    pub target: Vec<f64>
+}
+
+impl Input {
+    pub fn shape(&self) -> Vec<usize> {
+        match self {
+            Input::Dense(values) => vec![values.len()],
+            Input::Conv(channels) => conv_shape(channels),
+        }
+    }
+
+    /// Flattens a conv input channel by channel, row by row.
+    pub fn flatten(&self) -> Vec<f64> {
+        match self {
+            Input::Dense(values) => values.clone(),
+            Input::Conv(channels) => channels.iter().flatten().flatten().cloned().collect(),
+        }
+    }
+}
+
+/// `([channel][row][column] input, target)` in the form `Network::conv_train` expects.
+pub type ConvSample = (Vec<Vec<Vec<f64>>>, Vec<f64>);
+
+/// `[channels, rows, columns]` of a conv input.
+pub fn conv_shape(channels: &[Vec<Vec<f64>>]) -> Vec<usize> {
+    vec![
+        channels.len(),
+        channels.first().map_or(0, |rows| rows.len()),
+        channels.first().and_then(|rows| rows.first()).map_or(0, |row| row.len())
+    ]
+}
+
+/// Source of training or evaluation samples shared by the session, the trainer and the widgets.
+pub trait Dataset: Send + Sync {
+    fn len(&self) -> usize;
+
+    fn get(&self, index: usize) -> Sample;
+
+    /// Target of one sample, without building its input.
+    fn target(&self, index: usize) -> Vec<f64> {
+        self.get(index).target
+    }
+
+    fn input_shape(&self) -> Vec<usize>;
+
+    fn target_shape(&self) -> Vec<usize>;
+
+    /// Display name of every target index, e.g. digits or kanji tile ids.
+    fn label_names(&self) -> Option<&[String]> {
+        None
+    }
+
+    /// `[width, height]` when the samples are the pixels of one image in row order, see `CoordinateImage`.
+    fn image_size(&self) -> Option<[usize; 2]> {
+        None
+    }
+
+    /// `[min, max]` of every input dimension when the data was generated over known ranges, see `Function`.
+    fn input_ranges(&self) -> Option<Vec<[f64; 2]>> {
+        None
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All samples in the form `Network::dense_train` expects. Conv inputs are flattened.
+    fn dense_samples(&self) -> Vec<[Vec<f64>; 2]> {
+        (0..self.len()).map(|i| {
+            let sample = self.get(i);
+            [sample.input.flatten(), sample.target]
+        }).collect()
+    }
+
+    /// All samples in the form `Network::conv_train` expects. Dense inputs become a single one row channel.
+    fn conv_samples(&self) -> Vec<ConvSample> {
+        (0..self.len()).map(|i| {
+            let sample = self.get(i);
+            let input = match sample.input {
+                Input::Conv(channels) => channels,
+                Input::Dense(values) => vec![vec![values]],
+            };
+            (input, sample.target)
+        }).collect()
+    }
+}
+
+/// A view of some samples of another dataset, e.g. the validation part of a split.
+pub struct Subset {
+    pub source: Arc<dyn Dataset>,
+    pub indices: Vec<usize>
+}
+
+impl Subset {
+    pub fn new(source: Arc<dyn Dataset>, indices: Vec<usize>) -> Self {
+        Subset { source, indices }
+    }
+}
+
+impl Dataset for Subset {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn get(&self, index: usize) -> Sample {
+        self.source.get(self.indices[index])
+    }
+
+    fn target(&self, index: usize) -> Vec<f64> {
+        self.source.target(self.indices[index])
+    }
+
+    fn input_shape(&self) -> Vec<usize> {
+        self.source.input_shape()
+    }
+
+    fn target_shape(&self) -> Vec<usize> {
+        self.source.target_shape()
+    }
+
+    fn label_names(&self) -> Option<&[String]> {
+        self.source.label_names()
+    }
+
+    fn input_ranges(&self) -> Option<Vec<[f64; 2]>> {
+        self.source.input_ranges()
+    }
+}
+
+/// Splits off `fraction` of the samples for validation, returned as `(training, validation)`.
+/// Validation samples are spread evenly over the dataset so sorted data still gets every class in both parts.
+pub fn split_validation(dataset: Arc<dyn Dataset>, fraction: f64) -> (Arc<dyn Dataset>, Arc<dyn Dataset>) {
+    let len = dataset.len();
+    let count = ((len as f64 * fraction.clamp(0.0, 1.0)).round() as usize).min(len);
+    let mut validation: Vec<usize> = (0..count).map(|i| (i * len + len / 2) / count).collect();
+    validation.dedup();
+    let training = (0..len).filter(|i| validation.binary_search(i).is_err()).collect();
+    (
+        Arc::new(Subset::new(dataset.clone(), training)),
+        Arc::new(Subset::new(dataset, validation))
+    )
+}
+
+pub struct DenseDataset {
+    pub samples: Vec<[Vec<f64>; 2]>,
+    pub labels: Option<Vec<String>>
+}
+
+impl DenseDataset {
+    pub fn new(samples: Vec<[Vec<f64>; 2]>) -> Self {
+        DenseDataset { samples, labels: None }
+    }
+
+    pub fn with_labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+}
+
+impl Dataset for DenseDataset {
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn get(&self, index: usize) -> Sample {
+        let [input, target] = self.samples[index].clone();
+        Sample { input: Input::Dense(input), target }
+    }
+
+    fn target(&self, index: usize) -> Vec<f64> {
+        self.samples[index][1].clone()
+    }
+
+    fn input_shape(&self) -> Vec<usize> {
+        vec![self.samples.first().map_or(0, |sample| sample[0].len())]
+    }
+
+    fn target_shape(&self) -> Vec<usize> {
+        vec![self.samples.first().map_or(0, |sample| sample[1].len())]
+    }
+
+    fn label_names(&self) -> Option<&[String]> {
+        self.labels.as_deref()
+    }
+
+    fn dense_samples(&self) -> Vec<[Vec<f64>; 2]> {
+        self.samples.clone()
+    }
+}
+
+pub struct ConvDataset {
+    pub samples: Vec<ConvSample>,
+    pub labels: Option<Vec<String>>
+}
+
+impl ConvDataset {
+    pub fn new(samples: Vec<ConvSample>) -> Self {
+        ConvDataset { samples, labels: None }
+    }
+
+    pub fn with_labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+}
+
+impl Dataset for ConvDataset {
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn get(&self, index: usize) -> Sample {
+        let (input, target) = self.samples[index].clone();
+        Sample { input: Input::Conv(input), target }
+    }
+
+    fn target(&self, index: usize) -> Vec<f64> {
+        self.samples[index].1.clone()
+    }
+
+    fn input_shape(&self) -> Vec<usize> {
+        match self.samples.first() {
+            Some((input, _)) => conv_shape(input),
+            None => vec![0, 0, 0],
+        }
+    }
+
+    fn target_shape(&self) -> Vec<usize> {
+        vec![self.samples.first().map_or(0, |sample| sample.1.len())]
+    }
+
+    fn label_names(&self) -> Option<&[String]> {
+        self.labels.as_deref()
+    }
+
+    fn conv_samples(&self) -> Vec<ConvSample> {
+        self.samples.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sample `i` has input `[i]`, so split parts can be traced back to the source indices.
+    fn numbered(len: usize) -> Arc<dyn Dataset> {
+        Arc::new(DenseDataset::new((0..len).map(|i| [vec![i as f64], vec![0.0]]).collect()))
+    }
+
+    fn indices(dataset: &Arc<dyn Dataset>) -> Vec<usize> {
+        (0..dataset.len()).map(|i| dataset.get(i).input.flatten()[0] as usize).collect()
+    }
+
+    fn split(len: usize, fraction: f64) -> (Vec<usize>, Vec<usize>) {
+        let (training, validation) = split_validation(numbered(len), fraction);
+        (indices(&training), indices(&validation))
+    }
+
+    #[test]
+    fn split_nothing_off() {
+        assert_eq!(split(5, 0.0), (vec![0, 1, 2, 3, 4], vec![]));
+        assert_eq!(split(5, -1.0), (vec![0, 1, 2, 3, 4], vec![]));
+        assert_eq!(split(0, 0.5), (vec![], vec![]));
+    }
+
+    #[test]
+    fn split_everything_off() {
+        assert_eq!(split(5, 1.0), (vec![], vec![0, 1, 2, 3, 4]));
+        assert_eq!(split(5, 2.0), (vec![], vec![0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn split_spreads_validation() {
+        assert_eq!(split(10, 0.2), (vec![0, 1, 3, 4, 5, 6, 8, 9], vec![2, 7]));
+    }
+
+    #[test]
+    fn split_more_than_half() {
+        assert_eq!(split(10, 0.7), (vec![1, 4, 8], vec![0, 2, 3, 5, 6, 7, 9]));
+    }
+
+    #[test]
+    fn split_parts_are_disjoint_and_complete() {
+        for len in 0..40 {
+            for fraction in [0.0, 0.05, 0.1, 0.25, 0.5, 0.6, 0.9, 0.99, 1.0] {
+                let (training, validation) = split(len, fraction);
+                let expected = ((len as f64 * fraction).round() as usize).min(len);
+                assert_eq!(validation.len(), expected, "len {}, fraction {}", len, fraction);
+                let mut all: Vec<usize> = training.iter().chain(&validation).cloned().collect();
+                all.sort();
+                assert_eq!(all, (0..len).collect::<Vec<usize>>(), "len {}, fraction {}", len, fraction);
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::dataset::{Input, Sample};
+
+/// Random distortions applied to conv inputs every epoch. Every field at zero leaves the input unchanged.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Augmentation {
+    /// Largest shift in pixels along each axis.
+    pub shift: f64,
+    /// Largest rotation in degrees, either direction.
+    pub rotation: f64,
+    /// Largest relative zoom, `0.1` scales between 90% and 110%.
+    pub scale: f64,
+    /// Strength of the elastic distortion in pixels.
+    pub elastic: f64,
+    /// Smoothness of the elastic distortion field.
+    pub elastic_sigma: f64,
+    /// Standard deviation of the Gaussian noise added to every value.
+    pub noise: f64
+}
+
+impl Default for Augmentation {
+    fn default() -> Self {
+        Augmentation {
+            shift: 0.0,
+            rotation: 0.0,
+            scale: 0.0,
+            elastic: 0.0,
+            elastic_sigma: 4.0,
+            noise: 0.0
+        }
+    }
+}
+
+impl Augmentation {
+    pub fn is_identity(&self) -> bool {
+        self.shift == 0.0 && self.rotation == 0.0 && self.scale == 0.0 && self.elastic == 0.0 && self.noise == 0.0
+    }
+}
+
+/// Applies an `Augmentation` with its own seeded generator so augmented runs can be repeated.
+pub struct Augmenter {
+    pub augmentation: Augmentation,
+    pub seed: u64,
+    rng: StdRng
+}
+
+impl Augmenter {
+    pub fn new(augmentation: Augmentation, seed: u64) -> Self {
+        Augmenter {
+            augmentation,
+            seed,
+            rng: StdRng::seed_from_u64(seed)
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    pub fn augment_sample(&mut self, sample: Sample) -> Sample {
+        Sample { input: self.augment(sample.input), target: sample.target }
+    }
+
+    /// A distorted copy of a conv input, dense inputs have no spatial layout and are returned as they are.
+    pub fn augment(&mut self, input: Input) -> Input {
+        let channels = match input {
+            Input::Conv(channels) => channels,
+            dense => return dense,
+        };
+        let rows = channels.first().map_or(0, |channel| channel.len());
+        let columns = channels.first().and_then(|channel| channel.first()).map_or(0, |row| row.len());
+        if rows == 0 || columns == 0 {
+            return Input::Conv(channels);
+        }
+
+        let a = self.augmentation;
+        let angle = self.symmetric(a.rotation) * PI / 180.0;
+        let zoom = 1.0 + self.symmetric(a.scale);
+        let shift = [self.symmetric(a.shift), self.symmetric(a.shift)];
+        let displacement = if a.elastic > 0.0 {
+            Some([self.elastic_field(rows, columns), self.elastic_field(rows, columns)])
+        } else {
+            None
+        };
+
+        // Inverse mapping: for every output pixel find where it came from in the source image
+        let center = [(columns as f64 - 1.0) / 2.0, (rows as f64 - 1.0) / 2.0];
+        let (sin, cos) = angle.sin_cos();
+        let mut sources = vec![vec![[0.0, 0.0]; columns]; rows];
+        for y in 0..rows {
+            for x in 0..columns {
+                let mut dx = x as f64 - center[0] - shift[0];
+                let mut dy = y as f64 - center[1] - shift[1];
+                if let Some([field_x, field_y]) = &displacement {
+                    dx += field_x[y][x];
+                    dy += field_y[y][x];
+                }
+                sources[y][x] = [
+                    center[0] + (cos * dx + sin * dy) / zoom,
+                    center[1] + (-sin * dx + cos * dy) / zoom
+                ];
+            }
+        }
+
+        let mut augmented: Vec<Vec<Vec<f64>>> = channels.iter()
+            .map(|channel| sources.iter()
+                .map(|row| row.iter().map(|[x, y]| sample(channel, *x, *y)).collect())
+                .collect())
+            .collect();
+
+        if a.noise > 0.0 {
+            for value in augmented.iter_mut().flatten().flatten() {
+                *value += gaussian(&mut self.rng) * a.noise;
+            }
+        }
+        Input::Conv(augmented)
+    }
+
+    fn symmetric(&mut self, max: f64) -> f64 {
+        if max > 0.0 { self.rng.gen_range(-max..=max) } else { 0.0 }
+    }
+
+    /// Uniform noise blurred with a Gaussian of `elastic_sigma`, rescaled to `elastic` pixels.
+    fn elastic_field(&mut self, rows: usize, columns: usize) -> Vec<Vec<f64>> {
+        let noise: Vec<Vec<f64>> = (0..rows).map(|_| (0..columns).map(|_| self.rng.gen_range(-1.0..=1.0)).collect()).collect();
+        let sigma = self.augmentation.elastic_sigma.max(0.5);
+        let radius = (sigma * 3.0).ceil() as isize;
+        let kernel: Vec<f64> = (-radius..=radius).map(|i| (-(i * i) as f64 / (2.0 * sigma * sigma)).exp()).collect();
+        let total: f64 = kernel.iter().sum();
+
+        let blur = |get: &dyn Fn(isize) -> f64| -> f64 {
+            kernel.iter().enumerate().map(|(k, weight)| weight * get(k as isize - radius)).sum::<f64>() / total
+        };
+        let horizontal: Vec<Vec<f64>> = (0..rows).map(|y| (0..columns).map(|x| {
+            blur(&|offset| noise[y][(x as isize + offset).clamp(0, columns as isize - 1) as usize])
+        }).collect()).collect();
+        let field: Vec<Vec<f64>> = (0..rows).map(|y| (0..columns).map(|x| {
+            blur(&|offset| horizontal[(y as isize + offset).clamp(0, rows as isize - 1) as usize][x])
+        }).collect()).collect();
+
+        // Blurring shrinks the noise, normalise so `elastic` is the largest displacement
+        let max = field.iter().flatten().fold(0.0_f64, |max, value| max.max(value.abs()));
+        let strength = if max > 0.0 { self.augmentation.elastic / max } else { 0.0 };
+        field.into_iter().map(|row| row.into_iter().map(|value| value * strength).collect()).collect()
+    }
+}
+
+/// Standard normal value from the Box-Muller transform.
+pub fn gaussian<R: Rng>(rng: &mut R) -> f64 {
+    let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+/// Bilinear sample, zero outside the image.
+fn sample(channel: &[Vec<f64>], x: f64, y: f64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let pixel = |px: f64, py: f64| -> f64 {
+        if px < 0.0 || py < 0.0 || py as usize >= channel.len() || px as usize >= channel[0].len() {
+            0.0
+        } else {
+            channel[py as usize][px as usize]
+        }
+    };
+    let top = pixel(x0, y0) * (1.0 - fx) + pixel(x0 + 1.0, y0) * fx;
+    let bottom = pixel(x0, y0 + 1.0) * (1.0 - fx) + pixel(x0 + 1.0, y0 + 1.0) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
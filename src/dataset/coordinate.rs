@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use crate::dataset::image_folder::load_image;
+use crate::dataset::{ColorMode, Dataset, Input, Sample};
+use crate::error::GuiError;
+
+/// An image turned into a regression problem: the normalised `(x, y)` position of every pixel
+/// is the input and its intensity, one value per channel, is the target.
+/// Samples are stored row by row so network outputs can be drawn straight back into an image.
+pub struct CoordinateImage {
+    pub width: usize,
+    pub height: usize,
+    /// `[channel][row][column]` in `0.0..=1.0`.
+    pub pixels: Vec<Vec<Vec<f64>>>
+}
+
+impl CoordinateImage {
+    pub fn load<P: AsRef<Path>>(path: P, color: ColorMode) -> Result<Self, GuiError> {
+        Self::from_channels(load_image(path, color)?)
+    }
+
+    pub fn from_channels(pixels: Vec<Vec<Vec<f64>>>) -> Result<Self, GuiError> {
+        let height = pixels.first().map_or(0, |channel| channel.len());
+        let width = pixels.first().and_then(|channel| channel.first()).map_or(0, |row| row.len());
+        if width == 0 || height == 0 {
+            return Err(GuiError::Data("coordinate image has no pixels".to_string()));
+        }
+        Ok(CoordinateImage { width, height, pixels })
+    }
+
+    pub fn channels(&self) -> usize {
+        self.pixels.len()
+    }
+}
+
+/// Normalised input for pixel `(x, y)` of a `width` by `height` image, both axes in `0.0..=1.0`.
+pub fn coordinate(x: usize, y: usize, width: usize, height: usize) -> Vec<f64> {
+    let scale = |value: usize, size: usize| if size > 1 { value as f64 / (size - 1) as f64 } else { 0.0 };
+    vec![scale(x, width), scale(y, height)]
+}
+
+impl Dataset for CoordinateImage {
+    fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn get(&self, index: usize) -> Sample {
+        let (x, y) = (index % self.width, index / self.width);
+        Sample {
+            input: Input::Dense(coordinate(x, y, self.width, self.height)),
+            target: self.pixels.iter().map(|channel| channel[y][x]).collect()
+        }
+    }
+
+    fn input_shape(&self) -> Vec<usize> {
+        vec![2]
+    }
+
+    fn target_shape(&self) -> Vec<usize> {
+        vec![self.channels()]
+    }
+
+    fn image_size(&self) -> Option<[usize; 2]> {
+        Some([self.width, self.height])
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::dataset::augment::gaussian;
+use crate::dataset::{Dataset, Input, Sample};
+
+/// How the input space of a `Function` is sampled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Density {
+    /// Evenly spaced points along every dimension, ends included.
+    Grid(usize),
+    /// Uniformly random points in total.
+    Random(usize)
+}
+
+/// Builds dense samples from any function of any number of inputs.
+///
+/// ```ignore
+/// let dataset = Function::new(|x| vec![x[0].sin()])
+///     .with_range(-10.0, 10.0)
+///     .with_density(Density::Grid(200))
+///     .with_noise(0.05)
+///     .build();
+/// ```
+/// Target of an input point.
+pub type TargetFn = dyn Fn(&[f64]) -> Vec<f64> + Send + Sync;
+
+#[derive(Clone)]
+pub struct Function {
+    pub func: Arc<TargetFn>,
+    /// `[min, max]` of every input dimension.
+    pub ranges: Vec<[f64; 2]>,
+    pub density: Density,
+    /// Standard deviation of Gaussian noise added to the targets.
+    pub noise: f64,
+    pub seed: u64
+}
+
+impl Function {
+    pub fn new<F: Fn(&[f64]) -> Vec<f64> + Send + Sync + 'static>(func: F) -> Self {
+        Function {
+            func: Arc::new(func),
+            ranges: vec![],
+            density: Density::Grid(100),
+            noise: 0.0,
+            seed: 0
+        }
+    }
+
+    /// Adds an input dimension spanning `min..=max`.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.ranges.push([min, max]);
+        self
+    }
+
+    pub fn with_density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn with_noise(mut self, noise: f64) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Every input point of the configured density, without evaluating the function.
+    pub fn points(&self) -> Vec<Vec<f64>> {
+        let ranges = if self.ranges.is_empty() { vec![[-1.0, 1.0]] } else { self.ranges.clone() };
+        match self.density {
+            Density::Grid(count) => {
+                let axis = |[min, max]: [f64; 2]| -> Vec<f64> {
+                    match count {
+                        0 => vec![],
+                        1 => vec![(min + max) / 2.0],
+                        _ => (0..count).map(|i| min + (max - min) * i as f64 / (count - 1) as f64).collect(),
+                    }
+                };
+                // Cartesian product, the first dimension varies slowest
+                ranges.iter().fold(vec![vec![]], |points, range| {
+                    points.iter()
+                        .flat_map(|point| axis(*range).into_iter().map(move |value| {
+                            let mut next = point.clone();
+                            next.push(value);
+                            next
+                        }))
+                        .collect()
+                })
+            },
+            Density::Random(count) => {
+                let mut rng = StdRng::seed_from_u64(self.seed);
+                (0..count)
+                    .map(|_| ranges.iter().map(|[min, max]| if max > min { rng.gen_range(*min..=*max) } else { *min }).collect())
+                    .collect()
+            },
+        }
+    }
+
+    pub fn build(&self) -> FunctionDataset {
+        // A separate stream from the points so adding noise doesn't move random inputs
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(1));
+        let samples = self.points().into_iter()
+            .map(|input| {
+                let mut target = (self.func)(&input);
+                if self.noise > 0.0 {
+                    for value in target.iter_mut() {
+                        *value += gaussian(&mut rng) * self.noise;
+                    }
+                }
+                [input, target]
+            })
+            .collect();
+        FunctionDataset {
+            ranges: if self.ranges.is_empty() { vec![[-1.0, 1.0]] } else { self.ranges.clone() },
+            samples
+        }
+    }
+}
+
+/// Samples generated by a `Function`, remembering the ranges they were drawn from.
+pub struct FunctionDataset {
+    pub ranges: Vec<[f64; 2]>,
+    pub samples: Vec<[Vec<f64>; 2]>
+}
+
+impl Dataset for FunctionDataset {
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn get(&self, index: usize) -> Sample {
+        let [input, target] = self.samples[index].clone();
+        Sample { input: Input::Dense(input), target }
+    }
+
+    fn target(&self, index: usize) -> Vec<f64> {
+        self.samples[index][1].clone()
+    }
+
+    fn input_shape(&self) -> Vec<usize> {
+        vec![self.ranges.len()]
+    }
+
+    fn target_shape(&self) -> Vec<usize> {
+        vec![self.samples.first().map_or(0, |sample| sample[1].len())]
+    }
+
+    fn input_ranges(&self) -> Option<Vec<[f64; 2]>> {
+        Some(self.ranges.clone())
+    }
+
+    fn dense_samples(&self) -> Vec<[Vec<f64>; 2]> {
+        self.samples.clone()
+    }
+}
@@ -0,0 +1,261 @@
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use crate::dataset::image_folder::one_hot;
+use crate::dataset::{ConvDataset, DenseDataset};
+use crate::error::GuiError;
+
+const UNSIGNED_BYTE: u8 = 0x08;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Digits, the classes of MNIST and most IDX datasets.
+pub const DEFAULT_CLASSES: usize = 10;
+
+/// Reads MNIST style IDX image and label files, plain or gzip-compressed.
+///
+/// ```ignore
+/// let dataset = Idx::new("data/train-images-idx3-ubyte.gz", "data/train-labels-idx1-ubyte.gz")
+///     .with_limit(1000)
+///     .load_conv()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Idx {
+    pub images: PathBuf,
+    pub labels: PathBuf,
+    /// Only read the first `limit` samples.
+    pub limit: Option<usize>,
+    /// Width of the one-hot targets, labels must be below it.
+    pub classes: usize
+}
+
+/// Images, one-hot targets and label names read from an image and label file pair.
+type Loaded = (Vec<Vec<Vec<f64>>>, Vec<Vec<f64>>, Vec<String>);
+
+/// Contents of one IDX file, `dims[0]` is the number of items.
+pub struct IdxData {
+    pub dims: Vec<usize>,
+    pub data: Vec<u8>
+}
+
+impl Idx {
+    pub fn new<P: AsRef<Path>>(images: P, labels: P) -> Self {
+        Idx {
+            images: images.as_ref().to_path_buf(),
+            labels: labels.as_ref().to_path_buf(),
+            limit: None,
+            classes: DEFAULT_CLASSES
+        }
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Number of classes, fixed so the targets match the network whichever labels are loaded.
+    pub fn with_classes(mut self, classes: usize) -> Self {
+        self.classes = classes;
+        self
+    }
+
+    /// One `[1][rows][columns]` sample per image with values scaled to `0.0..=1.0` and one-hot targets.
+    pub fn load_conv(&self) -> Result<ConvDataset, GuiError> {
+        let (images, targets, labels) = self.read()?;
+        let samples = images.into_iter().map(|image| vec![image]).zip(targets).collect();
+        Ok(ConvDataset::new(samples).with_labels(labels))
+    }
+
+    /// Same as `load_conv` with every image flattened row by row.
+    pub fn load_dense(&self) -> Result<DenseDataset, GuiError> {
+        let (images, targets, labels) = self.read()?;
+        let samples = images.into_iter()
+            .zip(targets)
+            .map(|(image, target)| [image.into_iter().flatten().collect(), target])
+            .collect();
+        Ok(DenseDataset::new(samples).with_labels(labels))
+    }
+
+    fn read(&self) -> Result<Loaded, GuiError> {
+        let images = read_idx(&self.images, self.limit)?;
+        let labels = read_idx(&self.labels, self.limit)?;
+        let images_path = self.images.display().to_string();
+        let labels_path = self.labels.display().to_string();
+
+        if images.dims.len() != 3 {
+            return Err(GuiError::Data(format!("{}: expected 3 dimensions for images, found {}", images_path, images.dims.len())));
+        }
+        if labels.dims.len() != 1 {
+            return Err(GuiError::Data(format!("{}: expected 1 dimension for labels, found {}", labels_path, labels.dims.len())));
+        }
+        if images.dims[0] != labels.dims[0] {
+            return Err(GuiError::Data(format!("{} has {} images but {} has {} labels", images_path, images.dims[0], labels_path, labels.dims[0])));
+        }
+
+        let (rows, columns) = (images.dims[1], images.dims[2]);
+        let images = images.data.chunks(rows * columns)
+            .map(|image| image.chunks(columns)
+                .map(|row| row.iter().map(|pixel| *pixel as f64 / 255.0).collect())
+                .collect())
+            .collect();
+
+        if let Some(label) = labels.data.iter().find(|label| **label as usize >= self.classes) {
+            return Err(GuiError::Data(format!("{}: label {} is outside the {} classes", labels_path, label, self.classes)));
+        }
+        let targets = labels.data.iter().map(|label| one_hot(*label as usize, self.classes)).collect();
+        let names = (0..self.classes).map(|class| class.to_string()).collect();
+        Ok((images, targets, names))
+    }
+}
+
+/// Reads the header and at most `limit` items of an unsigned byte IDX file.
+/// Gzip-compressed files are detected from their first bytes.
+pub fn read_idx<P: AsRef<Path>>(path: P, limit: Option<usize>) -> Result<IdxData, GuiError> {
+    let display = path.as_ref().display().to_string();
+    let io_error = |e: io::Error| GuiError::io(&display, e);
+
+    let mut file = BufReader::new(File::open(path.as_ref()).map_err(io_error)?);
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic).map_err(io_error)?;
+
+    let header = Cursor::new(magic);
+    let mut reader: Box<dyn Read> = if magic[..2] == GZIP_MAGIC {
+        Box::new(GzDecoder::new(header.chain(file)))
+    } else {
+        Box::new(header.chain(file))
+    };
+
+    reader.read_exact(&mut magic).map_err(io_error)?;
+    if magic[0] != 0 || magic[1] != 0 {
+        return Err(GuiError::Data(format!("{}: not an IDX file", display)));
+    }
+    if magic[2] != UNSIGNED_BYTE {
+        return Err(GuiError::Data(format!("{}: only unsigned byte IDX files are supported, found type {:#04x}", display, magic[2])));
+    }
+
+    let mut dims = vec![];
+    for _ in 0..magic[3] {
+        let mut dim = [0u8; 4];
+        reader.read_exact(&mut dim).map_err(io_error)?;
+        dims.push(u32::from_be_bytes(dim) as usize);
+    }
+    if dims.iter().skip(1).any(|dim| *dim == 0) {
+        return Err(GuiError::Data(format!("{}: invalid header, item dimensions {:?} contain a zero", display, dims)));
+    }
+    if let (Some(items), Some(limit)) = (dims.first_mut(), limit) {
+        *items = (*items).min(limit);
+    }
+
+    let size = dims.iter()
+        .try_fold(1usize, |size, dim| size.checked_mul(*dim))
+        .ok_or_else(|| GuiError::Data(format!("{}: invalid header, dimensions {:?} are too large", display, dims)))?;
+    // Read as the data arrives rather than allocating what a corrupt header claims up front
+    let mut data = vec![];
+    reader.take(size as u64).read_to_end(&mut data).map_err(io_error)?;
+    if data.len() < size {
+        return Err(GuiError::Data(format!("{}: expected {} bytes of data for dimensions {:?}, found {}", display, size, dims, data.len())));
+    }
+    Ok(IdxData { dims, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+    use crate::dataset::Dataset;
+
+    fn idx_bytes(dims: &[u32], data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, UNSIGNED_BYTE, dims.len() as u8];
+        for dim in dims {
+            bytes.extend(dim.to_be_bytes());
+        }
+        bytes.extend(data);
+        bytes
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Writes `bytes` to a file only this test uses.
+    fn write(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("ml_gui_idx_{}_{}", std::process::id(), name));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    /// Three 2x2 images labelled 0, 1 and 2.
+    fn images() -> Vec<u8> {
+        idx_bytes(&[3, 2, 2], &[0, 255, 255, 0, 255, 255, 255, 255, 0, 0, 0, 0])
+    }
+
+    fn labels() -> Vec<u8> {
+        idx_bytes(&[3], &[0, 1, 2])
+    }
+
+    #[test]
+    fn reads_plain_files() {
+        let idx = Idx::new(write("plain_images", &images()), write("plain_labels", &labels()));
+        let dataset = idx.load_conv().unwrap();
+        assert_eq!(dataset.len(), 3);
+        let sample = dataset.get(0);
+        assert_eq!(sample.input.shape(), vec![1, 2, 2]);
+        assert_eq!(sample.target, one_hot(0, DEFAULT_CLASSES));
+        assert_eq!(dataset.get(2).target, one_hot(2, DEFAULT_CLASSES));
+    }
+
+    #[test]
+    fn reads_gzip_files() {
+        let plain = read_idx(write("gzip_plain", &images()), None).unwrap();
+        let compressed = read_idx(write("gzip_compressed", &gzip(&images())), None).unwrap();
+        assert_eq!(compressed.dims, vec![3, 2, 2]);
+        assert_eq!(compressed.data, plain.data);
+    }
+
+    #[test]
+    fn limit_keeps_first_items_and_class_count() {
+        let idx = Idx::new(write("limit_images", &images()), write("limit_labels", &labels())).with_limit(2);
+        let dataset = idx.load_dense().unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.get(1).input.flatten(), vec![1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(dataset.get(1).target.len(), DEFAULT_CLASSES);
+        assert_eq!(dataset.label_names().map(|names| names.len()), Some(DEFAULT_CLASSES));
+    }
+
+    #[test]
+    fn rejects_labels_outside_classes() {
+        let idx = Idx::new(write("classes_images", &images()), write("classes_labels", &labels())).with_classes(2);
+        assert!(matches!(idx.load_conv(), Err(GuiError::Data(_))));
+    }
+
+    #[test]
+    fn rejects_zero_dimensions() {
+        let path = write("zero_dims", &idx_bytes(&[3, 0, 2], &[]));
+        assert!(matches!(read_idx(path, None), Err(GuiError::Data(_))));
+    }
+
+    #[test]
+    fn rejects_overflowing_dimensions() {
+        let path = write("huge_dims", &idx_bytes(&[u32::MAX, u32::MAX, u32::MAX, u32::MAX, u32::MAX], &[]));
+        assert!(matches!(read_idx(path, None), Err(GuiError::Data(_))));
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let path = write("truncated", &idx_bytes(&[1000, 28, 28], &[0; 10]));
+        assert!(matches!(read_idx(path, None), Err(GuiError::Data(_))));
+    }
+
+    #[test]
+    fn rejects_non_idx_files() {
+        let path = write("not_idx", b"hello world");
+        assert!(matches!(read_idx(path, None), Err(GuiError::Data(_))));
+    }
+}
@@ -0,0 +1,205 @@
+extern crate image;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use image::*;
+use serde::Deserialize;
+
+use crate::dataset::{ConvDataset, DenseDataset};
+use crate::error::GuiError;
+
+/// How the label of every image is found.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LabelSource {
+    /// `{}` in the pattern marks the label inside the file stem, e.g. `mnist_{}` for `mnist_7.png`.
+    /// Files that don't match the pattern are skipped.
+    Filename(String),
+    /// Every subfolder is a class, e.g. `digits/7/a.png`.
+    Subfolder
+}
+
+/// Channels an image is converted into.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    /// One channel holding the average of red, green and blue.
+    Grayscale,
+    Rgb,
+    Rgba
+}
+
+impl ColorMode {
+    pub fn channels(&self) -> usize {
+        match self {
+            ColorMode::Grayscale => 1,
+            ColorMode::Rgb => 3,
+            ColorMode::Rgba => 4,
+        }
+    }
+}
+
+/// Image paths with their label index, and the sorted label names.
+type Scan = (Vec<(PathBuf, usize)>, Vec<String>);
+
+/// Loads every image of a directory into labelled samples with one-hot targets.
+///
+/// ```ignore
+/// let dataset = ImageFolder::new("assets/img/mnist")
+///     .with_labels(LabelSource::Filename("mnist_{}".to_string()))
+///     .load_conv()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImageFolder {
+    pub path: PathBuf,
+    pub labels: LabelSource,
+    pub color: ColorMode
+}
+
+impl ImageFolder {
+    /// Defaults to one class per subfolder and grayscale images.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        ImageFolder {
+            path: path.as_ref().to_path_buf(),
+            labels: LabelSource::Subfolder,
+            color: ColorMode::Grayscale
+        }
+    }
+
+    pub fn with_labels(mut self, labels: LabelSource) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn with_color(mut self, color: ColorMode) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Samples shaped `[channel][row][column]` for conv networks.
+    pub fn load_conv(&self) -> Result<ConvDataset, GuiError> {
+        let (images, labels) = self.scan()?;
+        let mut samples = vec![];
+        for (path, label) in images {
+            let input = load_image(&path, self.color)?;
+            samples.push((input, one_hot(label, labels.len())));
+        }
+        Ok(ConvDataset::new(samples).with_labels(labels))
+    }
+
+    /// Samples flattened channel by channel, row by row for dense networks.
+    pub fn load_dense(&self) -> Result<DenseDataset, GuiError> {
+        let (images, labels) = self.scan()?;
+        let mut samples = vec![];
+        for (path, label) in images {
+            let input = load_image(&path, self.color)?;
+            let flat = input.into_iter().flatten().flatten().collect();
+            samples.push([flat, one_hot(label, labels.len())]);
+        }
+        Ok(DenseDataset::new(samples).with_labels(labels))
+    }
+
+    /// Image paths with their label index, and the sorted label names.
+    fn scan(&self) -> Result<Scan, GuiError> {
+        let mut found: Vec<(PathBuf, String)> = vec![];
+        match &self.labels {
+            LabelSource::Filename(pattern) => {
+                let (prefix, suffix) = pattern.split_once("{}").ok_or(
+                    GuiError::Data(format!("label pattern {} has no {{}} placeholder", pattern))
+                )?;
+                for path in image_paths(&self.path)? {
+                    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                    if stem.len() > prefix.len() + suffix.len() && stem.starts_with(prefix) && stem.ends_with(suffix) {
+                        let label = stem[prefix.len()..stem.len() - suffix.len()].to_string();
+                        found.push((path, label));
+                    }
+                }
+            },
+            LabelSource::Subfolder => {
+                for folder in sorted_entries(&self.path)? {
+                    if !folder.is_dir() {
+                        continue;
+                    }
+                    let label = folder.file_name().and_then(|s| s.to_str()).unwrap_or("").to_string();
+                    for path in image_paths(&folder)? {
+                        found.push((path, label.clone()));
+                    }
+                }
+            },
+        }
+
+        if found.is_empty() {
+            return Err(GuiError::Data(format!("no labelled images found in {}", self.path.display())));
+        }
+
+        let mut labels: Vec<String> = found.iter().map(|(_, label)| label.clone()).collect();
+        sort_labels(&mut labels);
+        labels.dedup();
+
+        let images = found.into_iter()
+            .map(|(path, label)| {
+                let index = labels.iter().position(|l| *l == label).unwrap();
+                (path, index)
+            })
+            .collect();
+        Ok((images, labels))
+    }
+}
+
+/// Reads one image as `[channel][row][column]` with values scaled to `0.0..=1.0`.
+pub fn load_image<P: AsRef<Path>>(path: P, color: ColorMode) -> Result<Vec<Vec<Vec<f64>>>, GuiError> {
+    let path = path.as_ref();
+    let img = image::open(path).map_err(|e| GuiError::image(&path.display().to_string(), e))?;
+    let dims = img.dimensions();
+
+    let mut channels = vec![vec![vec![0.0; dims.0 as usize]; dims.1 as usize]; color.channels()];
+    for y in 0..dims.1 {
+        for x in 0..dims.0 {
+            let pixel = img.get_pixel(x, y).0;
+            let (row, column) = (y as usize, x as usize);
+            match color {
+                ColorMode::Grayscale => {
+                    let intensity = (pixel[0] as f64 + pixel[1] as f64 + pixel[2] as f64) / 3.0;
+                    channels[0][row][column] = intensity / 255.0;
+                },
+                ColorMode::Rgb | ColorMode::Rgba => {
+                    for c in 0..color.channels() {
+                        channels[c][row][column] = pixel[c] as f64 / 255.0;
+                    }
+                },
+            }
+        }
+    }
+    Ok(channels)
+}
+
+pub fn one_hot(index: usize, classes: usize) -> Vec<f64> {
+    let mut target = vec![0.0; classes];
+    target[index] = 1.0;
+    target
+}
+
+/// Numeric labels sort by value so `10` comes after `9`, anything else alphabetically.
+fn sort_labels(labels: &mut [String]) {
+    if labels.iter().all(|label| label.parse::<i64>().is_ok()) {
+        labels.sort_by_key(|label| label.parse::<i64>().unwrap());
+    } else {
+        labels.sort();
+    }
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>, GuiError> {
+    let display = dir.display().to_string();
+    let mut entries = vec![];
+    for entry in fs::read_dir(dir).map_err(|e| GuiError::io(&display, e))? {
+        entries.push(entry.map_err(|e| GuiError::io(&display, e))?.path());
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+fn image_paths(dir: &Path) -> Result<Vec<PathBuf>, GuiError> {
+    Ok(sorted_entries(dir)?.into_iter()
+        .filter(|path| path.is_file() && ImageFormat::from_path(path).is_ok())
+        .collect())
+}
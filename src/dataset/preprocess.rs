@@ -0,0 +1,334 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::{Dataset, Input, Sample};
+use crate::error::GuiError;
+
+/// One transformation of a sample input. Targets are never changed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    /// Multiplies every value, e.g. `1.0 / 255.0` for raw pixel intensities.
+    Scale { factor: f64 },
+    /// Subtracts the mean and divides by the standard deviation of every channel (conv) or feature (dense).
+    /// Left empty, both are computed from the training data by `Pipeline::fit`.
+    Standardize {
+        #[serde(default)]
+        mean: Vec<f64>,
+        #[serde(default)]
+        std: Vec<f64>
+    },
+    /// `max - value`, turns white digits on black into black on white and back.
+    Invert { max: f64 },
+    /// Collapses RGB(A) conv inputs into one channel with the Rec. 601 luma weights. Dense inputs are unchanged.
+    Grayscale,
+    /// Bilinear resize of conv inputs to the size the network expects. Dense inputs are unchanged.
+    Resize { rows: usize, columns: usize }
+}
+
+impl Step {
+    /// Standardization that still needs to be fitted.
+    pub fn standardize() -> Self {
+        Step::Standardize { mean: vec![], std: vec![] }
+    }
+
+    fn apply(&self, input: Input) -> Input {
+        match (self, input) {
+            (Step::Scale { factor }, input) => map_values(input, |value| value * factor),
+            (Step::Invert { max }, input) => map_values(input, |value| max - value),
+            (Step::Standardize { mean, std }, Input::Dense(values)) => Input::Dense(
+                values.into_iter().enumerate()
+                    .map(|(i, value)| standardize(value, mean.get(i), std.get(i)))
+                    .collect()
+            ),
+            (Step::Standardize { mean, std }, Input::Conv(channels)) => Input::Conv(
+                channels.into_iter().enumerate()
+                    .map(|(c, channel)| channel.into_iter()
+                        .map(|row| row.into_iter().map(|value| standardize(value, mean.get(c), std.get(c))).collect())
+                        .collect())
+                    .collect()
+            ),
+            (Step::Grayscale, Input::Conv(channels)) if channels.len() >= 3 => {
+                let (rows, columns) = (channels[0].len(), channels[0].first().map_or(0, |row| row.len()));
+                let mut gray = vec![vec![0.0; columns]; rows];
+                for y in 0..rows {
+                    for x in 0..columns {
+                        gray[y][x] = 0.299 * channels[0][y][x] + 0.587 * channels[1][y][x] + 0.114 * channels[2][y][x];
+                    }
+                }
+                Input::Conv(vec![gray])
+            },
+            (Step::Resize { rows, columns }, Input::Conv(channels)) => Input::Conv(
+                channels.iter().map(|channel| resize(channel, *rows, *columns)).collect()
+            ),
+            (_, input) => input,
+        }
+    }
+}
+
+/// Ordered preprocessing steps, applied identically to training data and at inference.
+/// Saved next to the model as `<model>.preprocess.json` so a loaded model sees the same inputs it was trained on.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Pipeline {
+    pub steps: Vec<Step>
+}
+
+impl Pipeline {
+    pub fn new(steps: Vec<Step>) -> Self {
+        Pipeline { steps }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn apply(&self, input: Input) -> Input {
+        self.steps.iter().fold(input, |input, step| step.apply(input))
+    }
+
+    pub fn apply_sample(&self, sample: Sample) -> Sample {
+        Sample { input: self.apply(sample.input), target: sample.target }
+    }
+
+    /// Computes the statistics of every unfitted `Standardize` step from `dataset`,
+    /// after the steps before it have been applied.
+    pub fn fit(&mut self, dataset: &dyn Dataset) {
+        for s in 0..self.steps.len() {
+            if let Step::Standardize { mean, .. } = &self.steps[s] {
+                if !mean.is_empty() || dataset.is_empty() {
+                    continue;
+                }
+            } else {
+                continue;
+            }
+
+            let before = Pipeline::new(self.steps[..s].to_vec());
+            let mut sums: Vec<f64> = vec![];
+            let mut squares: Vec<f64> = vec![];
+            let mut counts: Vec<f64> = vec![];
+            for i in 0..dataset.len() {
+                let groups: Vec<Vec<f64>> = match before.apply(dataset.get(i).input) {
+                    Input::Dense(values) => values.into_iter().map(|value| vec![value]).collect(),
+                    Input::Conv(channels) => channels.into_iter().map(|channel| channel.into_iter().flatten().collect()).collect(),
+                };
+                if sums.len() < groups.len() {
+                    sums.resize(groups.len(), 0.0);
+                    squares.resize(groups.len(), 0.0);
+                    counts.resize(groups.len(), 0.0);
+                }
+                for (g, values) in groups.iter().enumerate() {
+                    for value in values {
+                        sums[g] += value;
+                        squares[g] += value * value;
+                        counts[g] += 1.0;
+                    }
+                }
+            }
+
+            let mean: Vec<f64> = sums.iter().zip(&counts).map(|(sum, count)| sum / count.max(1.0)).collect();
+            let std = squares.iter().zip(&counts).zip(&mean)
+                .map(|((square, count), mean)| (square / count.max(1.0) - mean * mean).max(0.0).sqrt())
+                .collect();
+            self.steps[s] = Step::Standardize { mean, std };
+        }
+    }
+
+    pub fn file_name(model_name: &str) -> String {
+        format!("{}.preprocess.json", model_name)
+    }
+
+    pub fn save(&self, model_name: &str) -> Result<(), GuiError> {
+        let path = Self::file_name(model_name);
+        let json = serde_json::to_string_pretty(self).map_err(|e| GuiError::Model(format!("{}: {}", path, e)))?;
+        fs::write(&path, json).map_err(|e| GuiError::io(&path, e))
+    }
+
+    /// Deletes the pipeline saved with `model_name`, if there is one.
+    pub fn remove(model_name: &str) -> Result<(), GuiError> {
+        let path = Self::file_name(model_name);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(GuiError::io(&path, e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The pipeline saved with `model_name`, `None` for models saved without one.
+    pub fn load(model_name: &str) -> Result<Option<Self>, GuiError> {
+        let path = Self::file_name(model_name);
+        if !Path::new(&path).is_file() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&path).map_err(|e| GuiError::io(&path, e))?;
+        serde_json::from_str(&json).map(Some).map_err(|e| GuiError::Model(format!("{}: {}", path, e)))
+    }
+}
+
+/// A dataset seen through a preprocessing pipeline.
+pub struct Preprocessed {
+    pub source: Arc<dyn Dataset>,
+    pub pipeline: Arc<Pipeline>
+}
+
+impl Preprocessed {
+    pub fn new(source: Arc<dyn Dataset>, pipeline: Arc<Pipeline>) -> Self {
+        Preprocessed { source, pipeline }
+    }
+}
+
+impl Dataset for Preprocessed {
+    fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    fn get(&self, index: usize) -> Sample {
+        self.pipeline.apply_sample(self.source.get(index))
+    }
+
+    fn target(&self, index: usize) -> Vec<f64> {
+        self.source.target(index)
+    }
+
+    fn input_shape(&self) -> Vec<usize> {
+        match self.source.is_empty() {
+            true => self.source.input_shape(),
+            false => self.get(0).input.shape(),
+        }
+    }
+
+    fn target_shape(&self) -> Vec<usize> {
+        self.source.target_shape()
+    }
+
+    fn label_names(&self) -> Option<&[String]> {
+        self.source.label_names()
+    }
+
+    fn image_size(&self) -> Option<[usize; 2]> {
+        self.source.image_size()
+    }
+
+    fn input_ranges(&self) -> Option<Vec<[f64; 2]>> {
+        self.source.input_ranges()
+    }
+}
+
+fn map_values<F: Fn(f64) -> f64>(input: Input, f: F) -> Input {
+    match input {
+        Input::Dense(values) => Input::Dense(values.into_iter().map(&f).collect()),
+        Input::Conv(channels) => Input::Conv(
+            channels.into_iter()
+                .map(|channel| channel.into_iter().map(|row| row.into_iter().map(&f).collect()).collect())
+                .collect()
+        ),
+    }
+}
+
+fn standardize(value: f64, mean: Option<&f64>, std: Option<&f64>) -> f64 {
+    match (mean, std) {
+        (Some(mean), Some(std)) if *std > 0.0 => (value - mean) / std,
+        (Some(mean), _) => value - mean,
+        _ => value,
+    }
+}
+
+fn resize(channel: &[Vec<f64>], rows: usize, columns: usize) -> Vec<Vec<f64>> {
+    let (height, width) = (channel.len(), channel.first().map_or(0, |row| row.len()));
+    if height == 0 || width == 0 || (height == rows && width == columns) {
+        return channel.to_vec();
+    }
+    let scale = |size: usize, target: usize| if target > 1 { (size - 1) as f64 / (target - 1) as f64 } else { 0.0 };
+    let (scale_y, scale_x) = (scale(height, rows), scale(width, columns));
+
+    (0..rows).map(|y| {
+        let source_y = y as f64 * scale_y;
+        let (y0, fy) = (source_y.floor() as usize, source_y.fract());
+        let y1 = (y0 + 1).min(height - 1);
+        (0..columns).map(|x| {
+            let source_x = x as f64 * scale_x;
+            let (x0, fx) = (source_x.floor() as usize, source_x.fract());
+            let x1 = (x0 + 1).min(width - 1);
+            let top = channel[y0][x0] * (1.0 - fx) + channel[y0][x1] * fx;
+            let bottom = channel[y1][x0] * (1.0 - fx) + channel[y1][x1] * fx;
+            top * (1.0 - fy) + bottom * fy
+        }).collect()
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{ConvDataset, DenseDataset};
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn dense() -> DenseDataset {
+        DenseDataset::new(vec![[vec![2.0, 10.0], vec![1.0]], [vec![4.0, 30.0], vec![0.0]]])
+    }
+
+    #[test]
+    fn fit_after_earlier_steps() {
+        let mut pipeline = Pipeline::new(vec![Step::Scale { factor: 0.5 }, Step::standardize()]);
+        pipeline.fit(&dense());
+        assert_eq!(pipeline.steps[1], Step::Standardize { mean: vec![1.5, 10.0], std: vec![0.5, 5.0] });
+
+        let sample = pipeline.apply_sample(dense().get(0));
+        assert_close(&sample.input.flatten(), &[-1.0, -1.0]);
+        assert_eq!(sample.target, vec![1.0]);
+    }
+
+    #[test]
+    fn fit_keeps_fitted_steps() {
+        let fitted = Step::Standardize { mean: vec![0.0, 0.0], std: vec![2.0, 2.0] };
+        let mut pipeline = Pipeline::new(vec![fitted.clone()]);
+        pipeline.fit(&dense());
+        assert_eq!(pipeline.steps, vec![fitted]);
+
+        let mut empty = Pipeline::new(vec![Step::standardize()]);
+        empty.fit(&DenseDataset::new(vec![]));
+        assert_eq!(empty.steps, vec![Step::standardize()]);
+    }
+
+    #[test]
+    fn fit_conv_channels() {
+        let dataset = ConvDataset::new(vec![(vec![vec![vec![0.0, 2.0], vec![4.0, 6.0]], vec![vec![1.0, 1.0], vec![1.0, 1.0]]], vec![1.0])]);
+        let mut pipeline = Pipeline::new(vec![Step::standardize()]);
+        pipeline.fit(&dataset);
+        assert_eq!(pipeline.steps[0], Step::Standardize { mean: vec![3.0, 1.0], std: vec![5f64.sqrt(), 0.0] });
+
+        let input = pipeline.apply(dataset.get(0).input).flatten();
+        assert_close(&input[..4], &[-3.0 / 5f64.sqrt(), -1.0 / 5f64.sqrt(), 1.0 / 5f64.sqrt(), 3.0 / 5f64.sqrt()]);
+        assert_close(&input[4..], &[0.0; 4]);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let model = std::env::temp_dir().join(format!("ml_gui_preprocess_{}", std::process::id()));
+        let model = model.to_str().unwrap();
+        let mut pipeline = Pipeline::new(vec![
+            Step::Invert { max: 1.0 },
+            Step::Scale { factor: 2.0 },
+            Step::standardize(),
+            Step::Grayscale,
+            Step::Resize { rows: 3, columns: 3 }
+        ]);
+        pipeline.fit(&dense());
+        pipeline.save(model).unwrap();
+
+        let loaded = Pipeline::load(model).unwrap().unwrap();
+        assert_eq!(loaded, pipeline);
+        let input = dense().get(1).input;
+        assert_eq!(loaded.apply(input.clone()), pipeline.apply(input));
+
+        Pipeline::remove(model).unwrap();
+        assert_eq!(Pipeline::load(model).unwrap(), None);
+        Pipeline::remove(model).unwrap();
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::dataset::Dataset;
+use crate::evaluation;
+
+/// Order and selection of the samples the network is trained on.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Sampling {
+    /// Every sample in dataset order, the default.
+    Sequential,
+    /// Every sample, reshuffled before each epoch.
+    Shuffle,
+    /// `size` random samples drawn without replacement every tick.
+    RandomSubset { size: usize },
+    /// `size` samples every tick, picking each class equally often. Classes are the argmax of the target.
+    Balanced { size: usize }
+}
+
+impl fmt::Display for Sampling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sampling::Sequential => write!(f, "Sequential"),
+            Sampling::Shuffle => write!(f, "Shuffle"),
+            Sampling::RandomSubset { size } => write!(f, "Random {}", size),
+            Sampling::Balanced { size } => write!(f, "Balanced {}", size),
+        }
+    }
+}
+
+/// Picks training samples according to a `Sampling` strategy from a seeded generator,
+/// so a run can be repeated by reusing its seed.
+pub struct Sampler {
+    pub sampling: Sampling,
+    pub seed: u64,
+    rng: StdRng,
+    /// Sample indices of every class, built on first use of `Balanced`.
+    classes: Option<Vec<Vec<usize>>>
+}
+
+impl Sampler {
+    pub fn new(sampling: Sampling, seed: u64) -> Self {
+        Sampler {
+            sampling,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            classes: None
+        }
+    }
+
+    /// Seeds from the current time.
+    pub fn unseeded(sampling: Sampling) -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+        Self::new(sampling, seed)
+    }
+
+    /// Restarts the sequence from the seed.
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Forgets cached class indices, call whenever the dataset changes.
+    pub fn clear(&mut self) {
+        self.classes = None;
+    }
+
+    /// Whether every epoch needs its own call to `indices`.
+    pub fn per_epoch(&self) -> bool {
+        self.sampling == Sampling::Shuffle
+    }
+
+    pub fn indices(&mut self, dataset: &dyn Dataset) -> Vec<usize> {
+        let len = dataset.len();
+        match self.sampling {
+            Sampling::Sequential => (0..len).collect(),
+            Sampling::Shuffle => {
+                let mut indices: Vec<usize> = (0..len).collect();
+                indices.shuffle(&mut self.rng);
+                indices
+            },
+            Sampling::RandomSubset { size } => {
+                rand::seq::index::sample(&mut self.rng, len, size.min(len)).into_vec()
+            },
+            Sampling::Balanced { size } => {
+                let rng = &mut self.rng;
+                let classes = self.classes.get_or_insert_with(|| class_indices(dataset));
+                let filled: Vec<&Vec<usize>> = classes.iter().filter(|class| !class.is_empty()).collect();
+                if filled.is_empty() {
+                    return vec![];
+                }
+                let mut indices: Vec<usize> = (0..size).map(|i| {
+                    let class = filled[i % filled.len()];
+                    class[rng.gen_range(0..class.len())]
+                }).collect();
+                indices.shuffle(rng);
+                indices
+            },
+        }
+    }
+}
+
+/// Groups sample indices by the class of their target, as scored by [`evaluation::class`].
+fn class_indices(dataset: &dyn Dataset) -> Vec<Vec<usize>> {
+    let count = dataset.target_shape().iter().product::<usize>().max(2);
+    let mut classes = vec![vec![]; count];
+    for i in 0..dataset.len() {
+        if let Some(class) = evaluation::class(&dataset.target(i)) {
+            if class < classes.len() {
+                classes[class].push(i);
+            }
+        }
+    }
+    classes
+}
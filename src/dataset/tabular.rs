@@ -0,0 +1,334 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::dataset::DenseDataset;
+use crate::error::GuiError;
+
+/// A column picked by header name or by position.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Column {
+    Index(usize),
+    Name(String)
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.to_string())
+    }
+}
+
+impl Column {
+    fn find(&self, names: &[String]) -> Result<usize, String> {
+        match self {
+            Column::Index(i) if *i < names.len() => Ok(*i),
+            Column::Index(i) => Err(format!("column {} is out of range, the table has {} columns", i, names.len())),
+            Column::Name(name) => names.iter().position(|n| n == name)
+                .ok_or(format!("no column named {}", name)),
+        }
+    }
+}
+
+/// Turns CSV or JSON tables into dense samples.
+/// Numeric columns are used as they are, categorical columns are one-hot encoded in sorted order.
+///
+/// ```ignore
+/// let dataset = Tabular::new(vec!["sepal_length".into(), "petal_length".into()], vec!["species".into()])
+///     .with_categorical(vec!["species".into()])
+///     .load_csv("assets/data/iris.csv")?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tabular {
+    pub inputs: Vec<Column>,
+    pub targets: Vec<Column>,
+    pub categorical: Vec<Column>,
+    /// The first CSV row holds column names.
+    pub header: bool,
+    pub delimiter: u8
+}
+
+impl Tabular {
+    pub fn new(inputs: Vec<Column>, targets: Vec<Column>) -> Self {
+        Tabular {
+            inputs,
+            targets,
+            categorical: vec![],
+            header: true,
+            delimiter: b','
+        }
+    }
+
+    pub fn with_categorical(mut self, columns: Vec<Column>) -> Self {
+        self.categorical = columns;
+        self
+    }
+
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn load_csv<P: AsRef<Path>>(&self, path: P) -> Result<DenseDataset, GuiError> {
+        let display = path.as_ref().display().to_string();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(self.header)
+            .delimiter(self.delimiter)
+            .trim(csv::Trim::All)
+            .from_path(path.as_ref())
+            .map_err(|e| GuiError::Data(format!("{}: {}", display, e)))?;
+
+        let mut rows = vec![];
+        for record in reader.records() {
+            let record = record.map_err(|e| GuiError::Data(format!("{}: {}", display, e)))?;
+            rows.push(record.iter().map(|field| field.to_string()).collect::<Vec<String>>());
+        }
+
+        let names = if self.header {
+            reader.headers()
+                .map_err(|e| GuiError::Data(format!("{}: {}", display, e)))?
+                .iter().map(|name| name.to_string()).collect()
+        } else {
+            numbered(rows.first().map_or(0, |row| row.len()))
+        };
+        self.build(&names, &rows).map_err(|e| GuiError::Data(format!("{}: {}", display, e)))
+    }
+
+    /// Reads an array of objects keyed by column name, or an array of arrays addressed by index.
+    pub fn load_json<P: AsRef<Path>>(&self, path: P) -> Result<DenseDataset, GuiError> {
+        let display = path.as_ref().display().to_string();
+        let text = fs::read_to_string(path.as_ref()).map_err(|e| GuiError::io(&display, e))?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| GuiError::Data(format!("{}: {}", display, e)))?;
+
+        let records = match value {
+            Value::Array(records) => records,
+            _ => return Err(GuiError::Data(format!("{}: expected an array of records", display))),
+        };
+
+        let mut names: Vec<String> = vec![];
+        for record in &records {
+            match record {
+                Value::Object(fields) => for key in fields.keys() {
+                    if !names.contains(key) {
+                        names.push(key.clone());
+                    }
+                },
+                Value::Array(fields) => if fields.len() > names.len() {
+                    names = numbered(fields.len());
+                },
+                _ => return Err(GuiError::Data(format!("{}: records must be objects or arrays", display))),
+            }
+        }
+
+        let rows: Vec<Vec<String>> = records.iter().map(|record| match record {
+            Value::Object(fields) => names.iter().map(|name| fields.get(name).map_or(String::new(), cell)).collect(),
+            Value::Array(fields) => fields.iter().map(cell).collect(),
+            _ => vec![],
+        }).collect();
+        self.build(&names, &rows).map_err(|e| GuiError::Data(format!("{}: {}", display, e)))
+    }
+
+    fn build(&self, names: &[String], rows: &[Vec<String>]) -> Result<DenseDataset, String> {
+        let categorical = self.categorical.iter()
+            .map(|column| column.find(names))
+            .collect::<Result<Vec<usize>, String>>()?;
+        let encoders = |columns: &[Column]| -> Result<Vec<Encoder>, String> {
+            columns.iter().map(|column| {
+                let index = column.find(names)?;
+                Ok(if categorical.contains(&index) {
+                    Encoder::categories(index, rows)
+                } else {
+                    Encoder::Numeric(index)
+                })
+            }).collect()
+        };
+        let inputs = encoders(&self.inputs)?;
+        let targets = encoders(&self.targets)?;
+
+        let mut samples = vec![];
+        for (line, row) in rows.iter().enumerate() {
+            let mut sample: [Vec<f64>; 2] = [vec![], vec![]];
+            for (values, encoders) in sample.iter_mut().zip([&inputs, &targets]) {
+                for encoder in encoders {
+                    encoder.encode(row, values).map_err(|e| format!("row {}: {}", line + 1, e))?;
+                }
+            }
+            samples.push(sample);
+        }
+
+        let dataset = DenseDataset::new(samples);
+        // A single categorical target is a classification problem, keep its class names for the widgets.
+        Ok(match targets.as_slice() {
+            [Encoder::Categorical(_, categories)] => dataset.with_labels(categories.clone()),
+            _ => dataset,
+        })
+    }
+}
+
+enum Encoder {
+    Numeric(usize),
+    Categorical(usize, Vec<String>)
+}
+
+impl Encoder {
+    fn categories(index: usize, rows: &[Vec<String>]) -> Self {
+        let mut categories: Vec<String> = rows.iter().filter_map(|row| row.get(index).cloned()).collect();
+        categories.sort();
+        categories.dedup();
+        Encoder::Categorical(index, categories)
+    }
+
+    fn encode(&self, row: &[String], values: &mut Vec<f64>) -> Result<(), String> {
+        match self {
+            Encoder::Numeric(index) => {
+                let field = row.get(*index).ok_or(format!("missing column {}", index))?;
+                values.push(field.parse::<f64>().map_err(|_| format!("{} is not a number", field))?);
+            },
+            Encoder::Categorical(index, categories) => {
+                let field = row.get(*index).ok_or(format!("missing column {}", index))?;
+                values.extend(categories.iter().map(|category| if category == field { 1.0 } else { 0.0 }));
+            },
+        }
+        Ok(())
+    }
+}
+
+fn numbered(columns: usize) -> Vec<String> {
+    (0..columns).map(|i| i.to_string()).collect()
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Bool(flag) => (if *flag { "1" } else { "0" }).to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Writes `text` to a file only this test uses.
+    fn write(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("ml_gui_tabular_{}_{}", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn error(result: Result<DenseDataset, GuiError>) -> String {
+        result.err().expect("loading should fail").to_string()
+    }
+
+    const CSV: &str = "width, height, kind\n1.5, 2, dog\n3, 4, cat\n5, 6.5, dog\n";
+
+    #[test]
+    fn csv_columns_by_name_and_index() {
+        let dataset = Tabular::new(vec!["height".into(), 0.into()], vec![1.into()])
+            .load_csv(write("columns.csv", CSV))
+            .unwrap();
+        assert_eq!(dataset.samples, vec![
+            [vec![2.0, 1.5], vec![2.0]],
+            [vec![4.0, 3.0], vec![4.0]],
+            [vec![6.5, 5.0], vec![6.5]]
+        ]);
+        assert_eq!(dataset.labels, None);
+    }
+
+    #[test]
+    fn csv_categorical_target() {
+        let dataset = Tabular::new(vec!["width".into()], vec!["kind".into()])
+            .with_categorical(vec!["kind".into()])
+            .load_csv(write("categorical.csv", CSV))
+            .unwrap();
+        assert_eq!(dataset.samples, vec![
+            [vec![1.5], vec![0.0, 1.0]],
+            [vec![3.0], vec![1.0, 0.0]],
+            [vec![5.0], vec![0.0, 1.0]]
+        ]);
+        assert_eq!(dataset.labels, Some(vec!["cat".to_string(), "dog".to_string()]));
+    }
+
+    #[test]
+    fn csv_categorical_input() {
+        let dataset = Tabular::new(vec![2.into(), 0.into()], vec![1.into()])
+            .with_categorical(vec![2.into()])
+            .load_csv(write("categorical_input.csv", CSV))
+            .unwrap();
+        assert_eq!(dataset.samples[1], [vec![1.0, 0.0, 3.0], vec![4.0]]);
+        assert_eq!(dataset.labels, None);
+    }
+
+    #[test]
+    fn csv_without_header() {
+        let dataset = Tabular::new(vec![0.into()], vec![1.into()])
+            .with_header(false)
+            .with_delimiter(b';')
+            .load_csv(write("headless.csv", "1;2\n3;4\n"))
+            .unwrap();
+        assert_eq!(dataset.samples, vec![[vec![1.0], vec![2.0]], [vec![3.0], vec![4.0]]]);
+    }
+
+    #[test]
+    fn csv_errors() {
+        let path = write("errors.csv", CSV);
+        let unknown = error(Tabular::new(vec!["depth".into()], vec![]).load_csv(&path));
+        assert!(unknown.contains("no column named depth"));
+        let out_of_range = error(Tabular::new(vec![3.into()], vec![]).load_csv(&path));
+        assert!(out_of_range.contains("column 3 is out of range"));
+        let text = error(Tabular::new(vec!["kind".into()], vec![]).load_csv(&path));
+        assert!(text.contains("row 1: dog is not a number"));
+    }
+
+    #[test]
+    fn json_objects() {
+        let json = r#"[
+            {"x": 1, "label": "b", "flag": true},
+            {"x": 2.5, "label": "a", "flag": false},
+            {"label": "c", "x": -1, "flag": null}
+        ]"#;
+        let dataset = Tabular::new(vec!["x".into(), "flag".into()], vec!["label".into()])
+            .with_categorical(vec!["label".into(), "flag".into()])
+            .load_json(write("objects.json", json))
+            .unwrap();
+        assert_eq!(dataset.samples, vec![
+            [vec![1.0, 0.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]],
+            [vec![2.5, 0.0, 1.0, 0.0], vec![1.0, 0.0, 0.0]],
+            [vec![-1.0, 1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]]
+        ]);
+        assert_eq!(dataset.labels, Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn json_arrays() {
+        let dataset = Tabular::new(vec![0.into(), 1.into()], vec![2.into()])
+            .load_json(write("arrays.json", "[[1, 2, 3], [4, 5, 6]]"))
+            .unwrap();
+        assert_eq!(dataset.samples, vec![[vec![1.0, 2.0], vec![3.0]], [vec![4.0, 5.0], vec![6.0]]]);
+    }
+
+    #[test]
+    fn json_errors() {
+        let tabular = Tabular::new(vec![0.into()], vec![]);
+        assert!(tabular.load_json(write("object.json", r#"{"x": 1}"#)).is_err());
+        assert!(tabular.load_json(write("scalars.json", "[1, 2]")).is_err());
+        let missing = error(Tabular::new(vec!["y".into()], vec![]).load_json(write("missing.json", r#"[{"x": 1}]"#)));
+        assert!(missing.contains("no column named y"));
+    }
+}
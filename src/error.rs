@@ -0,0 +1,49 @@
+extern crate image;
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum GuiError {
+    Window(String),
+    Font(String),
+    Config(String),
+    Model(String),
+    Data(String),
+    Io { path: String, source: io::Error },
+    Image { path: String, source: image::ImageError }
+}
+
+impl GuiError {
+    pub fn io(path: &str, source: io::Error) -> Self {
+        GuiError::Io { path: path.to_string(), source }
+    }
+
+    pub fn image(path: &str, source: image::ImageError) -> Self {
+        GuiError::Image { path: path.to_string(), source }
+    }
+}
+
+impl fmt::Display for GuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuiError::Window(e) => write!(f, "Could not create window: {}", e),
+            GuiError::Font(e) => write!(f, "Could not load font: {}", e),
+            GuiError::Config(e) => write!(f, "Invalid config: {}", e),
+            GuiError::Model(e) => write!(f, "Model error: {}", e),
+            GuiError::Data(e) => write!(f, "Data error: {}", e),
+            GuiError::Io { path, source } => write!(f, "{}: {}", path, source),
+            GuiError::Image { path, source } => write!(f, "{}: {}", path, source),
+        }
+    }
+}
+
+impl std::error::Error for GuiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GuiError::Io { source, .. } => Some(source),
+            GuiError::Image { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use ml_library::loss_function::LossType;
+
+use crate::dataset::Dataset;
+
+/// Guards `ln(0)` for outputs that saturate at exactly zero.
+const EPSILON: f64 = 1e-12;
+
+/// Loss of a single sample, matching the loss the network is trained with.
+pub fn loss(loss_type: &LossType, outputs: &[f64], target: &[f64]) -> f64 {
+    match loss_type {
+        LossType::MSE => {
+            let sum: f64 = outputs.iter().zip(target).map(|(o, t)| (o - t).powi(2)).sum();
+            sum / outputs.len().max(1) as f64
+        },
+        LossType::CEL => {
+            -outputs.iter().zip(target).map(|(o, t)| t * (o + EPSILON).ln()).sum::<f64>()
+        },
+    }
+}
+
+/// Mean loss over every `(outputs, target)` pair.
+pub fn mean_loss(loss_type: &LossType, results: &[(Vec<f64>, Vec<f64>)]) -> f64 {
+    if results.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = results.iter().map(|(outputs, target)| loss(loss_type, outputs, target)).sum();
+    sum / results.len() as f64
+}
+
+/// Index of the largest value, `None` when there are no values.
+pub fn argmax(values: &[f64]) -> Option<usize> {
+    values.iter().enumerate()
+        .filter(|(_, value)| !value.is_nan())
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+/// Class of a network output or one-hot target: the argmax of several values,
+/// or `0` and `1` split at `0.5` for a single sigmoid output.
+pub fn class(values: &[f64]) -> Option<usize> {
+    match values {
+        [value] => Some((*value >= 0.5) as usize),
+        values => argmax(values),
+    }
+}
+
+/// Correct predictions of a classifier, counted per target class.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClassAccuracy {
+    pub correct: Vec<usize>,
+    pub total: Vec<usize>
+}
+
+impl ClassAccuracy {
+    pub fn new(outputs: &[Vec<f64>], targets: &[Vec<f64>]) -> Self {
+        let mut accuracy = ClassAccuracy::default();
+        for (output, target) in outputs.iter().zip(targets) {
+            accuracy.add(output, target);
+        }
+        accuracy
+    }
+
+    /// Scores `outputs` against the targets of `dataset`, in sample order.
+    pub fn from_dataset(outputs: &[Vec<f64>], dataset: &dyn Dataset) -> Self {
+        let mut accuracy = ClassAccuracy::default();
+        for (i, output) in outputs.iter().enumerate().take(dataset.len()) {
+            accuracy.add(output, &dataset.target(i));
+        }
+        accuracy
+    }
+
+    pub fn add(&mut self, output: &[f64], target: &[f64]) {
+        let (predicted, expected) = match (class(output), class(target)) {
+            (Some(predicted), Some(expected)) => (predicted, expected),
+            _ => return,
+        };
+        let classes = target.len().max(2).max(expected + 1);
+        if self.total.len() < classes {
+            self.total.resize(classes, 0);
+            self.correct.resize(classes, 0);
+        }
+        self.total[expected] += 1;
+        if predicted == expected {
+            self.correct[expected] += 1;
+        }
+    }
+
+    pub fn classes(&self) -> usize {
+        self.total.len()
+    }
+
+    /// Fraction of all samples classified correctly, `None` without samples.
+    pub fn overall(&self) -> Option<f64> {
+        let total: usize = self.total.iter().sum();
+        let correct: usize = self.correct.iter().sum();
+        (total > 0).then(|| correct as f64 / total as f64)
+    }
+
+    /// Fraction of the samples of `class` classified correctly, `None` when it has no samples.
+    pub fn class(&self, class: usize) -> Option<f64> {
+        match self.total.get(class) {
+            Some(&total) if total > 0 => Some(self.correct[class] as f64 / total as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Predicted against target class counts of a classifier, `counts[target][predicted]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Confusion {
+    pub counts: Vec<Vec<usize>>
+}
+
+impl Confusion {
+    pub fn new(outputs: &[Vec<f64>], targets: &[Vec<f64>]) -> Self {
+        let mut confusion = Confusion::default();
+        for (output, target) in outputs.iter().zip(targets) {
+            confusion.add(output, target);
+        }
+        confusion
+    }
+
+    /// Counts `outputs` against the targets of `dataset`, in sample order.
+    pub fn from_dataset(outputs: &[Vec<f64>], dataset: &dyn Dataset) -> Self {
+        let mut confusion = Confusion::default();
+        for (i, output) in outputs.iter().enumerate().take(dataset.len()) {
+            confusion.add(output, &dataset.target(i));
+        }
+        confusion
+    }
+
+    pub fn add(&mut self, output: &[f64], target: &[f64]) {
+        let (predicted, expected) = match (class(output), class(target)) {
+            (Some(predicted), Some(expected)) => (predicted, expected),
+            _ => return,
+        };
+        let classes = target.len().max(2).max(expected + 1).max(predicted + 1);
+        if self.counts.len() < classes {
+            for row in self.counts.iter_mut() {
+                row.resize(classes, 0);
+            }
+            self.counts.resize(classes, vec![0; classes]);
+        }
+        self.counts[expected][predicted] += 1;
+    }
+
+    pub fn classes(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Every row divided by its total, the fraction of each target class predicted as each class.
+    pub fn row_normalized(&self) -> Vec<Vec<f64>> {
+        self.counts.iter()
+            .map(|row| {
+                let total = row.iter().sum::<usize>().max(1) as f64;
+                row.iter().map(|count| *count as f64 / total).collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::DenseDataset;
+
+    fn one_hot() -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let outputs = vec![vec![0.9, 0.1], vec![0.2, 0.8], vec![0.6, 0.4]];
+        let targets = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 1.0]];
+        (outputs, targets)
+    }
+
+    fn binary() -> DenseDataset {
+        let targets = [1.0, 0.0, 1.0, 1.0];
+        DenseDataset::new(targets.iter().map(|target| [vec![0.0], vec![*target]]).collect())
+    }
+
+    fn binary_outputs() -> Vec<Vec<f64>> {
+        vec![vec![0.7], vec![0.2], vec![0.4], vec![0.9]]
+    }
+
+    #[test]
+    fn classes_of_outputs() {
+        assert_eq!(class(&[0.1, 0.7, 0.2]), Some(1));
+        assert_eq!(class(&[0.5]), Some(1));
+        assert_eq!(class(&[0.49]), Some(0));
+        assert_eq!(class(&[]), None);
+        assert_eq!(class(&[f64::NAN, 0.3]), Some(1));
+    }
+
+    #[test]
+    fn one_hot_accuracy() {
+        let (outputs, targets) = one_hot();
+        let accuracy = ClassAccuracy::new(&outputs, &targets);
+        assert_eq!(accuracy.correct, vec![1, 1]);
+        assert_eq!(accuracy.total, vec![1, 2]);
+        assert_eq!(accuracy.overall(), Some(2.0 / 3.0));
+        assert_eq!(accuracy.class(1), Some(0.5));
+        assert_eq!(accuracy.class(2), None);
+    }
+
+    #[test]
+    fn one_hot_confusion() {
+        let (outputs, targets) = one_hot();
+        let confusion = Confusion::new(&outputs, &targets);
+        assert_eq!(confusion.counts, vec![vec![1, 0], vec![1, 1]]);
+        assert_eq!(confusion.row_normalized(), vec![vec![1.0, 0.0], vec![0.5, 0.5]]);
+    }
+
+    #[test]
+    fn binary_accuracy() {
+        let accuracy = ClassAccuracy::from_dataset(&binary_outputs(), &binary());
+        assert_eq!(accuracy.classes(), 2);
+        assert_eq!(accuracy.correct, vec![1, 2]);
+        assert_eq!(accuracy.total, vec![1, 3]);
+        assert_eq!(accuracy.overall(), Some(0.75));
+    }
+
+    #[test]
+    fn binary_confusion() {
+        let confusion = Confusion::from_dataset(&binary_outputs(), &binary());
+        assert_eq!(confusion.classes(), 2);
+        assert_eq!(confusion.counts, vec![vec![1, 0], vec![1, 2]]);
+    }
+
+    #[test]
+    fn extra_outputs_are_ignored() {
+        let mut outputs = binary_outputs();
+        outputs.push(vec![0.0]);
+        assert_eq!(ClassAccuracy::from_dataset(&outputs, &binary()).total, vec![1, 3]);
+    }
+
+    #[test]
+    fn empty_dataset() {
+        let dataset = DenseDataset::new(vec![]);
+        let accuracy = ClassAccuracy::from_dataset(&binary_outputs(), &dataset);
+        assert_eq!(accuracy.classes(), 0);
+        assert_eq!(accuracy.overall(), None);
+        assert_eq!(accuracy.class(0), None);
+
+        let confusion = Confusion::from_dataset(&[], &dataset);
+        assert_eq!(confusion.classes(), 0);
+        assert!(confusion.row_normalized().is_empty());
+        assert_eq!(Confusion::new(&[], &[]), Confusion::default());
+    }
+
+    #[test]
+    fn mean_loss_of_nothing() {
+        assert_eq!(mean_loss(&LossType::MSE, &[]), 0.0);
+        assert_eq!(mean_loss(&LossType::MSE, &[(vec![1.0, 0.0], vec![0.0, 0.0])]), 0.5);
+    }
+}
@@ -0,0 +1,81 @@
+use image::Rgb;
+use serde::Deserialize;
+
+/// Colours for single channel network outputs. Multi-channel outputs are always written as RGB.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Colormap {
+    Gray,
+    Viridis,
+    Inferno,
+    Hot
+}
+
+impl Colormap {
+    fn stops(&self) -> &'static [[f64; 3]] {
+        match self {
+            Colormap::Gray => &[[0.0, 0.0, 0.0], [255.0, 255.0, 255.0]],
+            Colormap::Viridis => &[[68.0, 1.0, 84.0], [59.0, 82.0, 139.0], [33.0, 145.0, 140.0], [94.0, 201.0, 98.0], [253.0, 231.0, 37.0]],
+            Colormap::Inferno => &[[0.0, 0.0, 4.0], [87.0, 16.0, 110.0], [188.0, 55.0, 84.0], [249.0, 142.0, 9.0], [252.0, 255.0, 164.0]],
+            Colormap::Hot => &[[0.0, 0.0, 0.0], [230.0, 0.0, 0.0], [255.0, 210.0, 0.0], [255.0, 255.0, 255.0]],
+        }
+    }
+
+    /// Colour of `value` in `0.0..=1.0`, values outside are clamped.
+    pub fn color(&self, value: f64) -> Rgb<u8> {
+        let stops = self.stops();
+        let position = value.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - i as f64;
+        let channel = |c: usize| (stops[i][c] + (stops[i + 1][c] - stops[i][c]) * t).round() as u8;
+        Rgb([channel(0), channel(1), channel(2)])
+    }
+
+    /// Colours one pixel of network output, RGB outputs are used as they are.
+    pub fn pixel(&self, channels: &[f64]) -> Rgb<u8> {
+        if channels.len() >= 3 {
+            let channel = |c: usize| (channels[c].clamp(0.0, 1.0) * 255.0) as u8;
+            Rgb([channel(0), channel(1), channel(2)])
+        } else {
+            self.color(channels.first().cloned().unwrap_or(0.0))
+        }
+    }
+}
+
+/// Where and how `TrainingSession::export_image` renders a coordinate network.
+/// The network is continuous, so any resolution can be rendered regardless of the training image size.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub path: String,
+    pub width: usize,
+    pub height: usize,
+    pub colormap: Colormap
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            path: "Upscaled.png".to_string(),
+            width: 512,
+            height: 512,
+            colormap: Colormap::Gray
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn new(path: &str, width: usize, height: usize) -> Self {
+        ExportOptions {
+            path: path.to_string(),
+            width,
+            height,
+            colormap: Colormap::Gray
+        }
+    }
+
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+}
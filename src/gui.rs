@@ -1,345 +1,371 @@
 extern crate image;
 
 use graphics::clear;
-use ml_library::network::{Network, NetworkType};
+use ml_library::loss_function::LossType;
+use ml_library::network::Network;
 use opengl_graphics::GlGraphics;
 use piston::*;
 use glutin_window::*;
-use image::*;
 use piston_window::*;
 use rusttype::Font;
-use memory_stats::memory_stats;
 use std::process;
-use std::thread::{self, Thread};
-use std::time::Duration;
-use gfx_device_gl::Device;
+use std::sync::Arc;
 
-use crate::{section::Section, widget::WidgetType};
+use crate::{config::Config, export::ExportOptions, dataset::{Augmentation, ConvDataset, ConvSample, Dataset, DenseDataset, Sampling}, error::GuiError, layout::Layout, session::TrainingSession, trainer::{Trainer, TrainerCommand}, widget::{Rect, Widget, WidgetView}};
+
+const TICKS_PER_SECOND: u64 = 60;
+/// Window size the header, sidebar and font sizes were designed for.
+const BASE_SIZE: [f64; 2] = [1080.0, 480.0];
+const FONT_PATH: &str = "assets/fonts/BebasNeue-Regular.ttf";
 
 pub struct GUI<'a> {
     pub window: PistonWindow,
     pub gl: GlGraphics,
+    pub size: [f64; 2],
+    pub dpi: f64,
+    pub scale: f64,
     pub padding: [f64; 2],
     pub header: f64,
     pub sidebar: [f64; 2],
-    pub sections: Vec<Section>,
-    pub nn: Network,
-    pub dense_data: Vec<[Vec<f64>; 2]>,
-    pub conv_data: Vec<(Vec<Vec<Vec<f64>>>, Vec<f64>)>,
-    pub epochs_per_second: usize,
-    pub epochs: usize,
+    pub layout: Layout<Widget>,
+    pub session: Option<TrainingSession>,
+    pub trainer: Option<Trainer>,
     pub font: Font<'a>,
-    pub model_name: String,
-    pub x_range: [f64; 2],
-    pub will_train: bool
+    pub glyphs: Glyphs,
+    /// Last message or error shown at the bottom of the sidebar.
+    pub status: Option<Result<String, GuiError>>,
 }
 
 impl GUI<'_> {
-    pub fn new(nn: Network) -> Self {
-        let window: PistonWindow = WindowSettings::new("Netfix", [1080, 480])
+    pub fn new(nn: Network, loss: LossType) -> Result<Self, GuiError> {
+        Self::from_session(TrainingSession::new(nn, loss))
+    }
+
+    pub fn from_session(session: TrainingSession) -> Result<Self, GuiError> {
+        let mut window: PistonWindow = WindowSettings::new("Netfix", BASE_SIZE)
         .exit_on_esc(true)
         .build()
-        .unwrap();
-        
-        let dims = window.draw_size();
+        .map_err(|e| GuiError::Window(e.to_string()))?;
 
-        let font = Font::try_from_bytes(
-            include_bytes!("../assets/fonts/BebasNeue-Regular.ttf")).unwrap();
+        let font = Font::try_from_bytes(include_bytes!("../assets/fonts/BebasNeue-Regular.ttf"))
+            .ok_or(GuiError::Font("embedded BebasNeue-Regular.ttf is not a valid font".to_string()))?;
+        let glyphs = window.load_font(FONT_PATH)
+            .map_err(|e| GuiError::Font(format!("{}: {}", FONT_PATH, e)))?;
         
-        let padding = [dims.width * 0.01, dims.height * 0.02];
-        let header = dims.height * 0.12;
-        GUI {
+        let mut gui = GUI {
             window,
             gl: GlGraphics::new(OpenGL::V3_2),
-            sections: vec![],
-            padding,
-            header,
-            sidebar: [dims.width * 0.2, dims.height - padding[1] - header],
-            nn,
-            dense_data: vec![],
-            conv_data: vec![],
-            epochs_per_second: 1,
-            epochs: 0,
+            layout: Layout::columns(vec![]),
+            size: BASE_SIZE,
+            dpi: 1.0,
+            scale: 1.0,
+            padding: [0.0, 0.0],
+            header: 0.0,
+            sidebar: [0.0, 0.0],
+            session: Some(session),
+            trainer: None,
             font,
-            model_name: "Model".to_string(),
-            x_range: [-1.0, 1.0],
-            will_train: true
+            glyphs,
+            status: None,
+        };
+        let size = gui.window.size();
+        let draw_size = gui.window.draw_size();
+        gui.resize([size.width, size.height], [draw_size.width, draw_size.height]);
+        Ok(gui)
+    }
+
+    /// Builds the network, data, training settings and widget layout from a TOML or JSON file.
+    pub fn from_config(path: &str) -> Result<Self, GuiError> {
+        let config = Config::load(path)?;
+        let session = config.build_session()?;
+        let mut gui = Self::from_session(session)?;
+        gui.set_layout(config.layout.build());
+        Ok(gui)
+    }
+
+    /// Recomputes the header, sidebar and widget rectangles for a new window size.
+    /// Drawing happens in window points, `draw_size` is only used to render text at the native pixel density.
+    pub fn resize(&mut self, window_size: [f64; 2], draw_size: [f64; 2]) {
+        let [width, height] = window_size;
+        if width <= 0.0 || height <= 0.0 {
+            return;
         }
+        self.size = window_size;
+        self.dpi = (draw_size[0] / width).max(1.0);
+        self.scale = (width / BASE_SIZE[0]).min(height / BASE_SIZE[1]);
+        self.padding = [width * 0.01, height * 0.02];
+        self.header = height * 0.12;
+        self.sidebar = [width * 0.2, height - self.padding[1] - self.header];
+        self.relayout();
+    }
+
+    /// Lays out one column per inner vector, stacking its widgets vertically.
+    pub fn set_sections(&mut self, sections: Vec<Vec<Box<dyn WidgetView>>>) {
+        let columns = sections.into_iter()
+            .map(|widgets| Layout::rows(widgets.into_iter().map(Layout::leaf).collect()))
+            .collect();
+        self.set_layout(Layout::columns(columns));
     }
 
-    pub fn set_sections(&mut self, sections: Vec<Vec<WidgetType>>) {
-        let section_count = sections.len();
-        let section_width = ((self.window.draw_size().width - self.sidebar[0]) / section_count as f64) - self.padding[0];
-        let section_height = ((self.window.draw_size().height) - self.header) - self.padding[1];
-
-        let mut sects = vec![];
-
-        for i in 0..section_count {
-            let coords1 = [(section_width * i as f64) + self.padding[0] + self.sidebar[0], self.header];
-            let coords = [coords1[0] + self.padding[0],
-                coords1[1] + self.padding[1],
-                coords1[0] - self.padding[0],
-                coords1[1] - self.padding[1]
-            ];
-            sects.push(Section::new(coords, section_width, section_height));
-            sects[i].set_widgets(&sections[i]);
+    /// Replaces the widgets with an arbitrary layout tree of weighted splits and grids.
+    pub fn set_layout(&mut self, layout: Layout<Box<dyn WidgetView>>) {
+        self.layout = layout.map(&mut Widget::new);
+        self.relayout();
+
+        self.set_widget_data();
+    }
+
+    /// Area to the right of the sidebar and below the header that the layout fills.
+    pub fn content_area(&self) -> Rect {
+        let x = self.sidebar[0] + self.padding[0];
+        let y = self.header + self.padding[1];
+        [x, y, self.size[0] - x - self.padding[0], self.size[1] - y - self.padding[1]]
+    }
+
+    pub fn relayout(&mut self) {
+        let rects = self.layout.compute(self.content_area(), self.padding[0]);
+        for (widget, rect) in self.layout.leaves_mut().into_iter().zip(rects) {
+            widget.area = rect;
         }
-        self.sections = sects;
+    }
+
+    /// The session is moved onto the training thread while `run` is active.
+    pub fn session(&mut self) -> &mut TrainingSession {
+        self.session.as_mut().expect("training session is owned by the trainer thread while running")
     }
 
     pub fn set_model_name(&mut self, name: &str) {
-        self.model_name = name.to_string();
+        self.session().set_model_name(name);
     }
 
     pub fn set_epochs_per_second(&mut self, epochs: usize) {
-        self.epochs_per_second = epochs;
+        self.session().set_epochs_per_second(epochs);
     }
 
-    pub fn render(&mut self, evts: &Event, args: RenderArgs, window_ctx: &mut G2dTextureContext) {
+    pub fn render(&mut self, evts: &Event, _args: RenderArgs, window_ctx: &mut G2dTextureContext) {
 
-        let mut glyphs = self.window.load_font("assets/fonts/BebasNeue-Regular.ttf").unwrap();
+        let glyphs = &mut self.glyphs;
         let wall = self.padding[0];
         let line_space = self.padding[1] * 4.0;
-        let window_dims = self.window.draw_size();
-        let net_outputs = self.nn.get_nodes().last().unwrap().clone();
+        let window_width = self.size[0];
+        let dpi = self.dpi;
+        let title_size = 40.0 * self.scale;
+        let text_size = (18.0 * self.scale * dpi) as u32;
+        let (cost, epochs, learning_rate, validation_cost, sampling) = match self.trainer.as_ref().and_then(|t| t.latest.as_ref()) {
+            Some(snapshot) => (snapshot.cost, snapshot.epochs, snapshot.learning_rate, snapshot.validation_cost,
+                format!("{} (Seed {})", snapshot.sampling, snapshot.seed)),
+            None => (0.0, 0, 0.0, None, String::new()),
+        };
+        let status = match &self.status {
+            Some(Ok(message)) => Some(([1.0, 1.0, 1.0, 1.0], message.clone())),
+            Some(Err(e)) => Some(([1.0, 0.4, 0.4, 1.0], e.to_string())),
+            None => None,
+        };
+        let status_y = self.header + self.sidebar[1] - self.padding[1];
 
         self.window.draw_2d(evts, |ctx, gl, device| {
             clear([0.3, 0.3, 0.3, 1.0], gl);
 
-            for section in &mut self.sections {
-                section.render(ctx, gl, window_ctx);
+            for widget in self.layout.leaves_mut() {
+                widget.render(ctx, gl, window_ctx, glyphs);
             }
 
-        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], 40).round().draw(
-            &"NetFix",
-            &mut glyphs,
+        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], (title_size * dpi) as u32).round().draw(
+            "NetFix",
+            glyphs,
+            &ctx.draw_state,
+            ctx.transform.trans((window_width/2.0) - title_size, (self.header/2.0) + title_size / 2.0).zoom(1.0 / dpi), gl
+        );
+
+        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], text_size).draw(
+            &format!("Cost: {}", cost as f32),
+            glyphs,
             &ctx.draw_state,
-            ctx.transform.trans((window_dims.width/2.0) - 40.0, (self.header/2.0) + 20.0), gl
+            ctx.transform.trans(wall, self.header + self.padding[1] * 4.0).zoom(1.0 / dpi), gl
         );
 
-        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], 18).draw(
-            &format!("Cost: {}", self.nn.cost as f32),
-            &mut glyphs,
+        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], text_size).draw(
+            &format!("Epochs: {}", epochs),
+            glyphs,
             &ctx.draw_state,
-            ctx.transform.trans(wall, self.header + self.padding[1] * 4.0), gl
+            ctx.transform.trans(wall, (self.header + self.padding[1] * 4.0) + line_space * 1.0).zoom(1.0 / dpi), gl
         );
 
-        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], 18).draw(
-            &format!("Epochs: {}", self.epochs),
-            &mut glyphs,
+        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], text_size).draw(
+            &format!("Learning Rate: {}", learning_rate),
+            glyphs,
             &ctx.draw_state,
-            ctx.transform.trans(wall, (self.header + self.padding[1] * 4.0) + line_space * 1.0), gl
+            ctx.transform.trans(wall, (self.header + self.padding[1] * 4.0) + line_space * 2.0).zoom(1.0 / dpi), gl
         );
 
-        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], 18).draw(
-            &format!("Learning Rate: {}", self.nn.learning_rate),
-            &mut glyphs,
+        let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], text_size).draw(
+            &format!("Sampling: {}", sampling),
+            glyphs,
             &ctx.draw_state,
-            ctx.transform.trans(wall, (self.header + self.padding[1] * 4.0) + line_space * 2.0), gl
+            ctx.transform.trans(wall, (self.header + self.padding[1] * 4.0) + line_space * 3.0).zoom(1.0 / dpi), gl
         );
+
+        if let Some(validation_cost) = validation_cost {
+            let _ = text::Text::new_color([1.0, 1.0, 1.0, 1.0], text_size).draw(
+                &format!("Validation Cost: {}", validation_cost as f32),
+                glyphs,
+                &ctx.draw_state,
+                ctx.transform.trans(wall, (self.header + self.padding[1] * 4.0) + line_space * 4.0).zoom(1.0 / dpi), gl
+            );
+        }
+
+        if let Some((color, message)) = &status {
+            let _ = text::Text::new_color(*color, (text_size as f64 * 0.8) as u32).draw(
+                message,
+                glyphs,
+                &ctx.draw_state,
+                ctx.transform.trans(wall, status_y).zoom(1.0 / dpi), gl
+            );
+        }
         glyphs.factory.encoder.flush(device);
 
         });
     }
 
+    /// Trains on `dataset` and hands the same source to every widget.
+    pub fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        self.session().set_dataset(dataset);
+        self.set_widget_data();
+    }
+
     pub fn set_dense_data(&mut self, dense_data: Vec<[Vec<f64>; 2]>) {
-        self.dense_data = dense_data;
-        for i in 0..self.sections.len() {
-            let section = &mut self.sections[i];
-            for j in 0..section.widgets.len() {
-                let widget = &mut section.widgets[j];
-                widget.set_dense_data(self.dense_data.clone());
-            }
-        }
+        self.set_dataset(Arc::new(DenseDataset::new(dense_data)));
     }
 
-    pub fn set_conv_data(&mut self, conv_data: Vec<(Vec<Vec<Vec<f64>>>, Vec<f64>)>) {
-        self.conv_data = conv_data;
-        for i in 0..self.sections.len() {
-            let section = &mut self.sections[i];
-            for j in 0..section.widgets.len() {
-                let widget = &mut section.widgets[j];
-                widget.set_conv_data(self.conv_data.clone());
-            }
-        }
+    pub fn set_conv_data(&mut self, conv_data: Vec<ConvSample>) {
+        self.set_dataset(Arc::new(ConvDataset::new(conv_data)));
     }
 
-    pub fn set_cost_expiration(&mut self, expire: bool, epochs: usize) {
-        for i in 0..self.sections.len() {
-            for j in 0..self.sections[i].widgets.len() {
-                self.sections[i].widgets[j].set_cost_expiration(expire, epochs);
-            }
-        }
+    /// Shuffles, subsamples or class-balances the training data, see `Sampling`.
+    pub fn set_sampling(&mut self, sampling: Sampling, seed: Option<u64>) {
+        self.session().set_sampling(sampling, seed);
+    }
+
+    /// Resolution, path and colormap used when `U` renders the coordinate network.
+    pub fn set_export(&mut self, options: ExportOptions) {
+        self.session().set_export(options);
+    }
+
+    /// Trains conv networks on randomly distorted samples, previewed by the `AugmentPreview` widget.
+    pub fn set_augmentation(&mut self, augmentation: Augmentation) {
+        self.session().set_augmentation(augmentation);
     }
 
-    pub fn pop_cost(&mut self) {
-        for i in 0..self.sections.len() {
-            for j in 0..self.sections[i].widgets.len() {
-                self.sections[i].widgets[j].pop_cost();
+    /// Evaluates `validation` every `interval` epochs and plots it next to the training cost.
+    pub fn set_validation(&mut self, validation: Arc<dyn Dataset>, interval: usize) {
+        let session = self.session();
+        session.set_validation(validation);
+        session.set_validation_interval(interval);
+        self.set_widget_data();
+    }
+
+    /// Holds out `fraction` of the current data for validation.
+    pub fn split_validation(&mut self, fraction: f64, interval: usize) {
+        let session = self.session();
+        session.split_validation(fraction);
+        session.set_validation_interval(interval);
+        self.set_widget_data();
+    }
+
+    /// Hands the session's training and validation data to every widget.
+    fn set_widget_data(&mut self) {
+        let dataset = self.session().dataset.clone();
+        let validation = self.session().validation.clone();
+        for widget in self.layout.leaves_mut() {
+            widget.view.set_dataset(dataset.clone());
+            if let Some(validation) = &validation {
+                widget.view.set_validation(validation.clone());
             }
         }
     }
 
     pub fn run(&mut self) {
-        let mut events = Events::new(EventSettings::new()).ups(60);
+        let session = self.session.take().expect("GUI is already running");
+        self.trainer = Some(Trainer::spawn(session, TICKS_PER_SECOND));
+
+        let mut events = Events::new(EventSettings::new()).ups(TICKS_PER_SECOND);
         while let Some(e) = events.next(&mut self.window) {
+            if let Some(args) = e.resize_args() {
+                let draw_size = [args.draw_size[0] as f64, args.draw_size[1] as f64];
+                self.resize(args.window_size, draw_size);
+            }
+
             if let Some(args) = e.render_args() {
                 let window_ctx = &mut self.window.create_texture_context();
                 self.render(&e, args, window_ctx);
             }
 
-            if let Some(args) = e.update_args() {
-                if self.will_train {
-                    if self.nn.network_type == NetworkType::FCN {
-                        self.nn.dense_train(self.dense_data.clone(), self.epochs_per_second);
-                    } else {
-                        self.nn.conv_train(self.conv_data.clone(), self.epochs_per_second)
+            if e.update_args().is_some() {
+                let (snapshots, restarted) = match self.trainer.as_mut() {
+                    Some(trainer) => (trainer.poll(), trainer.take_restarted()),
+                    None => (vec![], false),
+                };
+                if let Some(status) = self.trainer.as_mut().and_then(|trainer| trainer.status.take()) {
+                    self.status = Some(status);
+                }
+                if restarted {
+                    for widget in self.layout.leaves_mut() {
+                        widget.view.reset();
+                    }
+                }
+                for snapshot in snapshots {
+                    for widget in self.layout.leaves_mut() {
+                        widget.view.update(&snapshot);
                     }
-                    self.epochs += self.epochs_per_second;
-                    let nn_dense_data = self.get_network_outputs();
-                    for i in 0..self.sections.len() {
-                        let weights = self.nn.get_weights();
-                        let biases = self.nn.get_biases();
-                        let nodes = self.nn.get_nodes();
-                        let conv_outputs = self.nn.get_conv_outputs();
-                        self.sections[i].update(
-                            self.nn.cost, 
-                            self.epochs_per_second, 
-                            nn_dense_data.clone(),
-                            (weights, biases, nodes, conv_outputs)
-                        );
+                }
+                for widget in self.layout.leaves_mut() {
+                    if let Some(e) = widget.view.take_error() {
+                        self.status = Some(Err(e));
                     }
                 }
             }
 
-            if let Some(Button::Keyboard(key)) = e.press_args() {
+            let mut used = false;
+            if let Some(button) = e.press_args() {
+                // Every widget sees the button, e.g. so all cost plots switch scale together
+                for widget in self.layout.leaves_mut() {
+                    used |= widget.view.handle_input(&button);
+                }
+            }
+
+            if let (Some(Button::Keyboard(key)), false) = (e.press_args(), used) {
                 match key {
                     Key::F => 
-                        for i in 0..self.dense_data.len() {
-                            let mut outputs = vec![];
-                            if self.nn.network_type == NetworkType::FCN {
-                                outputs = self.nn.dense_forward(self.dense_data[i][0].clone());
-                            }
-                            println!("------------------------\n{i}) Input: {:?} Output: {:?} Target: {:?}",
-                                self.dense_data[i][0], 
-                                outputs, 
-                                self.dense_data[i][1]
-                            );
-                        },
+                        self.send(TrainerCommand::PrintOutputs),
                     Key::I => 
-                        self.save_img(),
+                        self.send(TrainerCommand::SaveImage),
+                    Key::U => 
+                        self.send(TrainerCommand::ExportImage),
                     Key::R => 
-                        self.restart(),
-                    Key::Backspace =>
-                        self.pop_cost(),
+                        self.send(TrainerCommand::Reset),
                     Key::S => 
-                        {
-                            self.nn.save_model(&self.model_name);
-                            println!("{} Saved Succesfully!", self.model_name);
-                        },
+                        self.send(TrainerCommand::Save),
                     Key::L =>
-                        {
-                            self.restart();
-                            self.nn.load_model(&self.model_name);
-                            println!("{} Loaded Succesfully!", self.model_name);
-                        },
+                        self.send(TrainerCommand::Load),
                     Key::Space =>
-                        {
-                            if self.will_train {
-                                self.will_train = false;
-                            } else {
-                                self.will_train = true;
-                            }
-                        },
+                        self.send(TrainerCommand::ToggleTraining),
                     Key::Escape => 
                         {
                             process::exit(0);
                         }
-                    _ => 
-                        println!("No Function Associated With That Button"),
+                    _ => {}
                 }
             }
         }
-    }
 
-    fn restart(&mut self) {
-        for i in 0..self.sections.len() {
-            self.sections[i].cost = 0.0;
-            for j in 0..self.sections[i].widgets.len() {
-                let widget = &mut self.sections[i].widgets[j];
-                widget.cost = vec![];
-                widget.epochs = 0;
-            }
+        if let Some(trainer) = self.trainer.take() {
+            self.session = trainer.shutdown();
         }
-        self.epochs = 0;
-        self.nn.reset();
     }
 
-    fn get_network_outputs(&mut self) -> Vec<Vec<f64>> {
-        let mut outputs = vec![];
-        for i in 0..self.dense_data.len() {
-            let mut out = vec![];
-            if self.nn.network_type == NetworkType::FCN {
-                out = self.nn.dense_forward(self.dense_data[i][0].clone());
-            } else {
-                out = self.nn.conv_forward(self.conv_data[i].0.clone());
-            }
-            outputs.push(out); 
+    fn send(&self, command: TrainerCommand) {
+        if let Some(trainer) = &self.trainer {
+            trainer.send(command);
         }
-        outputs
-    }
-
-    fn get_dense_network_img(&mut self) -> Vec<Vec<u8>> {
-
-        let width = 28;
-        let height = 28;
-        
-        let mut new_image: Vec<Vec<u8>> = vec![vec![0; width]; height];
-
-        for y in 0..height {
-            for x in 0..width {
-                let x_coord = x as f64 / (width - 1) as f64;
-                let y_coord = y as f64 / (height - 1) as f64;
-                let inputs = vec![x_coord, y_coord];        
-                let outputs = self.nn.dense_forward(inputs);
-                let int = (outputs[0] * 255.0) as u8;
-                new_image[y][x] = int;
-            }
-        }  
-        new_image
-    }
-    
-    fn get_expected_img(&mut self) -> Vec<Vec<u8>> {
-        
-        let mut new_image: Vec<Vec<u8>> = vec![vec![0; 28]; 28];
-        if self.dense_data.len() == 784 {
-            let mut index = 0;
-
-            for y in 0..new_image.len() as usize {
-                for x in 0..new_image[y].len() as usize {
-                    let int = self.dense_data[index][1][0] * 255.0;
-                    new_image[y][x] = int as u8;
-                    index += 1;
-                }
-            }  
-        }
-        new_image
-    }
-
-    fn save_img(&mut self) { 
-        let image = self.get_dense_network_img();
-
-        let mut img = RgbImage::new(28, 28);
-
-        for y in 0..img.dimensions().1 as usize {
-            for x in 0..img.dimensions().0 as usize {
-                let int = image[y][x];
-                let rgb = Rgb([int, int, int]);
-                img.put_pixel(x as u32, y as u32, rgb);
-            }
-        }  
-
-        let _  = img.save("Output.png").unwrap();
-        println!("Saved Image");
     }
 }
\ No newline at end of file
@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+
+/// Buckets kept per resolution level.
+pub const DEFAULT_CAPACITY: usize = 2048;
+
+/// Summary of consecutive recorded values, wide enough to draw a min/max envelope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bucket {
+    pub first_epoch: usize,
+    pub last_epoch: usize,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: usize
+}
+
+impl Bucket {
+    pub fn new(epoch: usize, value: f64) -> Self {
+        Bucket {
+            first_epoch: epoch,
+            last_epoch: epoch,
+            min: value,
+            max: value,
+            sum: value,
+            count: 1
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count.max(1) as f64
+    }
+
+    /// Extends this bucket with `other`, which must come after it.
+    pub fn merge(&mut self, other: &Bucket) {
+        self.last_epoch = other.last_epoch;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+}
+
+struct Level {
+    /// Values summarised by every complete bucket.
+    size: usize,
+    buckets: VecDeque<Bucket>,
+    /// Bucket still filling up, always the newest data.
+    pending: Option<Bucket>,
+    /// Whether old buckets have been dropped, after which the level only covers recent epochs.
+    rolling: bool
+}
+
+impl Level {
+    fn covers(&self, epoch: usize) -> bool {
+        !self.rolling || self.buckets.front().is_some_and(|bucket| bucket.first_epoch <= epoch)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Bucket> {
+        self.buckets.iter().chain(self.pending.iter())
+    }
+}
+
+/// Values over epochs in bounded memory, however long training runs.
+///
+/// Level 0 keeps the most recent `capacity` values as they are, every following level
+/// summarises twice as many values per bucket and so reaches twice as far back.
+/// The coarsest level always spans the whole run, a new one is added whenever it fills up.
+pub struct History {
+    levels: Vec<Level>,
+    capacity: usize,
+    len: usize
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        History {
+            levels: vec![Level { size: 1, buckets: VecDeque::new(), pending: None, rolling: false }],
+            capacity: capacity.max(2),
+            len: 0
+        }
+    }
+
+    /// Number of values recorded since the last `clear`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::with_capacity(self.capacity);
+    }
+
+    pub fn last(&self) -> Option<Bucket> {
+        self.levels[0].iter().last().cloned()
+    }
+
+    pub fn first_epoch(&self) -> Option<usize> {
+        self.levels.last().and_then(|level| level.iter().next()).map(|bucket| bucket.first_epoch)
+    }
+
+    pub fn push(&mut self, epoch: usize, value: f64) {
+        let point = Bucket::new(epoch, value);
+        let top = self.levels.len() - 1;
+        for (k, level) in self.levels.iter_mut().enumerate() {
+            match level.pending.as_mut() {
+                Some(pending) => pending.merge(&point),
+                None => level.pending = Some(point),
+            }
+            if level.pending.is_some_and(|pending| pending.count >= level.size) {
+                level.buckets.extend(level.pending.take());
+                if k < top && level.buckets.len() > self.capacity {
+                    level.buckets.pop_front();
+                    level.rolling = true;
+                }
+            }
+        }
+        self.len += 1;
+
+        if self.levels[top].buckets.len() > self.capacity {
+            self.grow();
+        }
+    }
+
+    /// Adds a coarser level built from the current coarsest one, which then starts rolling.
+    fn grow(&mut self) {
+        let top = self.levels.last_mut().unwrap();
+        let mut buckets = VecDeque::with_capacity(top.buckets.len() / 2 + 1);
+        let mut leftover = None;
+        for pair in top.buckets.iter().collect::<Vec<_>>().chunks(2) {
+            let mut merged = *pair[0];
+            match pair.get(1) {
+                Some(second) => {
+                    merged.merge(second);
+                    buckets.push_back(merged);
+                },
+                None => leftover = Some(merged),
+            }
+        }
+        let pending = match (leftover, top.pending) {
+            (Some(mut leftover), Some(pending)) => {
+                leftover.merge(&pending);
+                Some(leftover)
+            },
+            (leftover, pending) => leftover.or(pending),
+        };
+        let size = top.size * 2;
+
+        while top.buckets.len() > self.capacity {
+            top.buckets.pop_front();
+            top.rolling = true;
+        }
+        self.levels.push(Level { size, buckets, pending, rolling: false });
+    }
+
+    /// Buckets from `from_epoch` onwards, from the finest level that still reaches back that far,
+    /// merged down to at most `max_buckets` so drawing cost doesn't grow with the run.
+    pub fn view(&self, from_epoch: usize, max_buckets: usize) -> Vec<Bucket> {
+        let level = self.levels.iter()
+            .find(|level| level.covers(from_epoch))
+            .unwrap_or_else(|| self.levels.last().unwrap());
+        let buckets: Vec<Bucket> = level.iter()
+            .filter(|bucket| bucket.last_epoch >= from_epoch)
+            .cloned()
+            .collect();
+        downsample(&buckets, max_buckets)
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Merges neighbouring buckets so at most `max_buckets` remain.
+pub fn downsample(buckets: &[Bucket], max_buckets: usize) -> Vec<Bucket> {
+    let group = buckets.len().div_ceil(max_buckets.max(1));
+    if group <= 1 {
+        return buckets.to_vec();
+    }
+    buckets.chunks(group)
+        .map(|chunk| {
+            let mut merged = chunk[0];
+            for bucket in &chunk[1..] {
+                merged.merge(bucket);
+            }
+            merged
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(capacity: usize, count: usize) -> History {
+        let mut history = History::with_capacity(capacity);
+        for epoch in 0..count {
+            history.push(epoch, epoch as f64);
+        }
+        history
+    }
+
+    #[test]
+    fn keeps_every_value_within_capacity() {
+        let history = filled(8, 8);
+        let buckets = history.view(0, 100);
+        assert_eq!(buckets.len(), 8);
+        assert!(buckets.iter().enumerate().all(|(epoch, bucket)| bucket.count == 1 && bucket.first_epoch == epoch));
+    }
+
+    #[test]
+    fn overflow_still_spans_the_run() {
+        let history = filled(4, 100);
+        assert_eq!(history.len(), 100);
+        assert_eq!(history.first_epoch(), Some(0));
+        assert_eq!(history.last(), Some(Bucket::new(99, 99.0)));
+
+        let buckets = history.view(0, 100);
+        assert!(buckets.len() <= 5);
+        assert_eq!(buckets.first().unwrap().first_epoch, 0);
+        assert_eq!(buckets.last().unwrap().last_epoch, 99);
+        assert_eq!(buckets.iter().map(|bucket| bucket.count).sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn recent_epochs_stay_fine() {
+        let history = filled(4, 100);
+        let buckets = history.view(97, 100);
+        assert_eq!(buckets, vec![Bucket::new(97, 97.0), Bucket::new(98, 98.0), Bucket::new(99, 99.0)]);
+    }
+
+    #[test]
+    fn min_max_survive_merging() {
+        let mut history = History::with_capacity(4);
+        for epoch in 0..64 {
+            let value = match epoch {
+                13 => -50.0,
+                40 => 80.0,
+                _ => 1.0,
+            };
+            history.push(epoch, value);
+        }
+        let buckets = history.view(0, 100);
+        assert!(buckets.len() < 64);
+        assert_eq!(buckets.iter().map(|bucket| bucket.min).fold(f64::INFINITY, f64::min), -50.0);
+        assert_eq!(buckets.iter().map(|bucket| bucket.max).fold(f64::NEG_INFINITY, f64::max), 80.0);
+        assert_eq!(buckets.iter().map(|bucket| bucket.sum).sum::<f64>(), 62.0 - 50.0 + 80.0);
+    }
+
+    #[test]
+    fn clear_keeps_capacity() {
+        let mut history = filled(4, 100);
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.first_epoch(), None);
+        assert_eq!(history.last(), None);
+        assert!(history.view(0, 100).is_empty());
+
+        for epoch in 0..5 {
+            history.push(epoch, 0.0);
+        }
+        assert!(history.view(0, 100).len() < 5);
+    }
+
+    #[test]
+    fn downsample_merges_neighbours() {
+        let buckets: Vec<Bucket> = (0..10).map(|epoch| Bucket::new(epoch, epoch as f64)).collect();
+        let merged = downsample(&buckets, 3);
+        assert_eq!(merged.len(), 3);
+        assert_eq!((merged[0].first_epoch, merged[0].last_epoch, merged[0].min, merged[0].max), (0, 3, 0.0, 3.0));
+        assert_eq!((merged[2].first_epoch, merged[2].last_epoch, merged[2].count), (8, 9, 2));
+        assert_eq!(downsample(&buckets, 20), buckets);
+    }
+}
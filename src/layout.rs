@@ -0,0 +1,252 @@
+use crate::widget::Rect;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    /// Children are placed side by side, left to right.
+    Horizontal,
+    /// Children are stacked top to bottom.
+    Vertical
+}
+
+/// Tree describing where widgets go. Leaves hold the widgets themselves, containers only hold weights.
+pub enum Layout<T> {
+    Leaf(T),
+    Split {
+        direction: Direction,
+        children: Vec<(f64, Layout<T>)>
+    },
+    Grid {
+        rows: Vec<f64>,
+        columns: Vec<f64>,
+        cells: Vec<Cell<T>>
+    }
+}
+
+/// A grid entry covering `row_span` rows and `column_span` columns starting at `row`, `column`.
+pub struct Cell<T> {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub layout: Layout<T>
+}
+
+impl<T> Layout<T> {
+    pub fn leaf(item: T) -> Self {
+        Layout::Leaf(item)
+    }
+
+    pub fn horizontal(children: Vec<(f64, Layout<T>)>) -> Self {
+        Layout::Split { direction: Direction::Horizontal, children }
+    }
+
+    pub fn vertical(children: Vec<(f64, Layout<T>)>) -> Self {
+        Layout::Split { direction: Direction::Vertical, children }
+    }
+
+    /// Equal width columns.
+    pub fn columns(children: Vec<Layout<T>>) -> Self {
+        Self::horizontal(children.into_iter().map(|child| (1.0, child)).collect())
+    }
+
+    /// Equal height rows.
+    pub fn rows(children: Vec<Layout<T>>) -> Self {
+        Self::vertical(children.into_iter().map(|child| (1.0, child)).collect())
+    }
+
+    /// Empty grid with the given row and column weights, filled in with `cell`.
+    pub fn grid(rows: Vec<f64>, columns: Vec<f64>) -> Self {
+        Layout::Grid { rows, columns, cells: vec![] }
+    }
+
+    /// Adds a cell to a grid. Positions and spans are clamped to the grid size, calls on other nodes are ignored.
+    pub fn cell(mut self, row: usize, column: usize, row_span: usize, column_span: usize, layout: Layout<T>) -> Self {
+        if let Layout::Grid { cells, .. } = &mut self {
+            cells.push(Cell { row, column, row_span: row_span.max(1), column_span: column_span.max(1), layout });
+        }
+        self
+    }
+
+    /// Rectangles of every leaf inside `area`, in the same order as `leaves`.
+    /// Neighbouring children are separated by `gap`.
+    pub fn compute(&self, area: Rect, gap: f64) -> Vec<Rect> {
+        let mut rects = vec![];
+        self.compute_into(area, gap, &mut rects);
+        rects
+    }
+
+    fn compute_into(&self, area: Rect, gap: f64, rects: &mut Vec<Rect>) {
+        match self {
+            Layout::Leaf(_) => rects.push(area),
+            Layout::Split { direction, children } => {
+                let weights: Vec<f64> = children.iter().map(|(weight, _)| *weight).collect();
+                let areas = split(area, *direction, &weights, gap);
+                for ((_, child), child_area) in children.iter().zip(areas) {
+                    child.compute_into(child_area, gap, rects);
+                }
+            },
+            Layout::Grid { rows, columns, cells } => {
+                let row_bounds = bounds(area[1], area[3], rows, gap);
+                let column_bounds = bounds(area[0], area[2], columns, gap);
+                for cell in cells {
+                    // Every leaf needs a rect so `compute` stays aligned with `leaves`
+                    if row_bounds.is_empty() || column_bounds.is_empty() {
+                        let count = cell.layout.leaves().len();
+                        rects.extend(std::iter::repeat_n([area[0], area[1], 0.0, 0.0], count));
+                        continue;
+                    }
+                    // Cells placed outside the grid are moved into its last row or column
+                    let row = cell.row.min(row_bounds.len() - 1);
+                    let column = cell.column.min(column_bounds.len() - 1);
+                    let last_row = (row + cell.row_span).min(row_bounds.len()) - 1;
+                    let last_column = (column + cell.column_span).min(column_bounds.len()) - 1;
+                    let x = column_bounds[column][0];
+                    let y = row_bounds[row][0];
+                    let cell_area = [x, y, column_bounds[last_column][1] - x, row_bounds[last_row][1] - y];
+                    cell.layout.compute_into(cell_area, gap, rects);
+                }
+            }
+        }
+    }
+
+    pub fn leaves(&self) -> Vec<&T> {
+        let mut leaves = vec![];
+        self.visit(&mut |leaf| leaves.push(leaf));
+        leaves
+    }
+
+    pub fn leaves_mut(&mut self) -> Vec<&mut T> {
+        let mut leaves = vec![];
+        self.visit_mut(&mut leaves);
+        leaves
+    }
+
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a T)) {
+        match self {
+            Layout::Leaf(item) => f(item),
+            Layout::Split { children, .. } => children.iter().for_each(|(_, child)| child.visit(f)),
+            Layout::Grid { cells, .. } => cells.iter().for_each(|cell| cell.layout.visit(f)),
+        }
+    }
+
+    fn visit_mut<'a>(&'a mut self, leaves: &mut Vec<&'a mut T>) {
+        match self {
+            Layout::Leaf(item) => leaves.push(item),
+            Layout::Split { children, .. } => children.iter_mut().for_each(|(_, child)| child.visit_mut(leaves)),
+            Layout::Grid { cells, .. } => cells.iter_mut().for_each(|cell| cell.layout.visit_mut(leaves)),
+        }
+    }
+
+    /// Converts every leaf while keeping the shape of the tree.
+    pub fn map<U>(self, f: &mut impl FnMut(T) -> U) -> Layout<U> {
+        match self {
+            Layout::Leaf(item) => Layout::Leaf(f(item)),
+            Layout::Split { direction, children } => Layout::Split {
+                direction,
+                children: children.into_iter().map(|(weight, child)| (weight, child.map(f))).collect()
+            },
+            Layout::Grid { rows, columns, cells } => Layout::Grid {
+                rows,
+                columns,
+                cells: cells.into_iter().map(|cell| Cell {
+                    row: cell.row,
+                    column: cell.column,
+                    row_span: cell.row_span,
+                    column_span: cell.column_span,
+                    layout: cell.layout.map(f)
+                }).collect()
+            },
+        }
+    }
+}
+
+/// Divides `area` along `direction` proportionally to `weights`, leaving `gap` between neighbours.
+pub fn split(area: Rect, direction: Direction, weights: &[f64], gap: f64) -> Vec<Rect> {
+    match direction {
+        Direction::Horizontal => bounds(area[0], area[2], weights, gap).into_iter()
+            .map(|[start, end]| [start, area[1], end - start, area[3]])
+            .collect(),
+        Direction::Vertical => bounds(area[1], area[3], weights, gap).into_iter()
+            .map(|[start, end]| [area[0], start, area[2], end - start])
+            .collect(),
+    }
+}
+
+/// Start and end of each weighted span along one axis.
+fn bounds(start: f64, length: f64, weights: &[f64], gap: f64) -> Vec<[f64; 2]> {
+    if weights.is_empty() {
+        return vec![];
+    }
+    let total: f64 = weights.iter().map(|weight| weight.max(0.0)).sum();
+    let available = (length - gap * (weights.len() - 1) as f64).max(0.0);
+
+    let mut spans = vec![];
+    let mut position = start;
+    for weight in weights {
+        let size = if total > 0.0 {
+            available * weight.max(0.0) / total
+        } else {
+            available / weights.len() as f64
+        };
+        spans.push([position, position + size]);
+        position += size + gap;
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(count: usize) -> Vec<Layout<usize>> {
+        (0..count).map(Layout::leaf).collect()
+    }
+
+    #[test]
+    fn weighted_split() {
+        let layout = Layout::horizontal(vec![(1.0, Layout::leaf(0)), (3.0, Layout::leaf(1))]);
+        assert_eq!(layout.compute([0.0, 0.0, 100.0, 50.0], 0.0), vec![[0.0, 0.0, 25.0, 50.0], [25.0, 0.0, 75.0, 50.0]]);
+    }
+
+    #[test]
+    fn gap_between_children() {
+        let layout = Layout::rows(layout(2));
+        assert_eq!(layout.compute([0.0, 0.0, 10.0, 110.0], 10.0), vec![[0.0, 0.0, 10.0, 50.0], [0.0, 60.0, 10.0, 50.0]]);
+    }
+
+    #[test]
+    fn nested_splits() {
+        let layout = Layout::columns(vec![Layout::leaf(0), Layout::rows(vec![Layout::leaf(1), Layout::leaf(2)])]);
+        let rects = layout.compute([0.0, 0.0, 100.0, 100.0], 0.0);
+        assert_eq!(rects, vec![[0.0, 0.0, 50.0, 100.0], [50.0, 0.0, 50.0, 50.0], [50.0, 50.0, 50.0, 50.0]]);
+        assert_eq!(layout.leaves(), vec![&0, &1, &2]);
+    }
+
+    #[test]
+    fn grid_spans() {
+        let layout = Layout::grid(vec![1.0, 1.0], vec![1.0, 1.0])
+            .cell(0, 0, 1, 2, Layout::leaf(0))
+            .cell(1, 0, 1, 1, Layout::leaf(1))
+            .cell(1, 1, 5, 5, Layout::leaf(2));
+        let rects = layout.compute([0.0, 0.0, 100.0, 100.0], 0.0);
+        assert_eq!(rects, vec![[0.0, 0.0, 100.0, 50.0], [0.0, 50.0, 50.0, 50.0], [50.0, 50.0, 50.0, 50.0]]);
+    }
+
+    #[test]
+    fn grid_cell_out_of_bounds() {
+        let layout = Layout::grid(vec![1.0], vec![1.0, 1.0])
+            .cell(0, 5, 1, 1, Layout::leaf(0))
+            .cell(0, 0, 1, 1, Layout::leaf(1))
+            .cell(0, 1, 1, 1, Layout::leaf(2));
+        let rects = layout.compute([0.0, 0.0, 100.0, 100.0], 0.0);
+        assert_eq!(rects.len(), layout.leaves().len());
+        assert_eq!(rects, vec![[50.0, 0.0, 50.0, 100.0], [0.0, 0.0, 50.0, 100.0], [50.0, 0.0, 50.0, 100.0]]);
+    }
+
+    #[test]
+    fn empty_grid_keeps_leaves_aligned() {
+        let layout = Layout::grid(vec![], vec![1.0])
+            .cell(0, 0, 1, 1, Layout::rows(layout(2)));
+        assert_eq!(layout.compute([10.0, 20.0, 100.0, 100.0], 0.0), vec![[10.0, 20.0, 0.0, 0.0]; 2]);
+    }
+}
@@ -1,3 +1,14 @@
+pub mod config;
+pub mod dataset;
+pub mod error;
+pub mod evaluation;
+pub mod export;
 pub mod gui;
-pub mod section;
-pub mod widget;
\ No newline at end of file
+pub mod history;
+pub mod layout;
+pub mod plot;
+pub mod session;
+pub mod snapshot;
+pub mod trainer;
+pub mod widget;
+pub mod widgets;
\ No newline at end of file
@@ -1,37 +1,74 @@
-use ml_gui::{gui::GUI, widget::WidgetType};
-use ml_library::{layer::Layer, layer::LayerType::*, loss_function::LossType::*, network::Network, conv_params::PaddingType::*, activation::ActivationFunction::*};
-use image::*;
-use WidgetType::*;
+use std::sync::Arc;
+
+use ml_gui::{config::Config, dataset::{Augmentation, ColorMode, ConvSample, CoordinateImage, Density, Function, ImageFolder, LabelSource, Pipeline, Step}, error::GuiError, evaluation, gui::GUI, session::TrainingSession, widget::WidgetView, widgets::*};
+use ml_library::{layer::Layer, loss_function::LossType::*, network::Network, conv_params::PaddingType::*, activation::ActivationFunction::*};
 
 fn main() {
-    
+    let args: Vec<String> = std::env::args().collect();
+    let headless = args.iter().any(|arg| arg == "--headless");
+    let value = |name: &str| args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1));
+    let config = value("--config");
+    let steps = value("--steps").and_then(|steps| steps.parse().ok()).unwrap_or(100);
+    let export = value("--export");
+    let size = value("--size").and_then(|size| size.split_once('x'))
+        .and_then(|(width, height)| Some([width.parse().ok()?, height.parse().ok()?]));
 
     // nn.load_model("assets/models/cnnTanH");
     // conv_digit_test();
-    conv_digit_model();
+    let result = match (config, headless) {
+        (Some(path), false) => GUI::from_config(path).map(|mut app| app.run()),
+        (Some(path), true) => Config::load(path)
+            .and_then(|config| config.build_session())
+            .and_then(|mut session| {
+                train_headless(&mut session, steps);
+                match export {
+                    Some(export) => upscale_img(&mut session, export, size),
+                    None => Ok(()),
+                }
+            }),
+        (None, true) => conv_digit_session().map(|mut session| train_headless(&mut session, steps)),
+        (None, false) => conv_digit_model(),
+    };
     // xor_model()
     // dense_digit_model(app);
     // softmax_test();
+
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+pub fn train_headless(session: &mut TrainingSession, steps: usize) {
+    for _ in 0..steps {
+        session.step();
+        println!("Epochs: {} Cost: {}", session.epochs, session.nn.cost);
+    }
+}
+
+pub fn xor_model() -> Result<(), GuiError> {
+    let sections: Vec<Vec<Box<dyn WidgetView>>> = vec![
+        vec![Box::new(CostPlot::new())], 
+        vec![Box::new(Architecture::new())]
+    ];
+
+    let mut app = GUI::from_session(xor_session())?;
+    app.set_sections(sections);
+    app.run();
+    Ok(())
 }
 
-pub fn xor_model() {
+pub fn xor_session() -> TrainingSession {
 
     let layers: Vec<Layer> = vec![
         Layer::dense([2, 3], Sigmoid),
         Layer::dense([3, 2], Sigmoid),
     ];
 
-    let mut nn = Network::new(layers, 0.5, 2, MSE);
-    let sections: Vec<Vec<WidgetType>> = vec![
-        vec![CostPlot], 
-        vec![Architecture]
-    ];
+    let nn = Network::new(layers, 0.5, 2, MSE);
 
-    let mut app = GUI::new(nn);
-    app.set_sections(sections);
-    app.set_epochs_per_second(20);
-    app.set_cost_expiration(false, 20);
-    app.set_model_name("assets/models/XOR");
+    let mut session = TrainingSession::new(nn, MSE);
+    session.set_epochs_per_second(20);
+    session.set_model_name("assets/models/XOR");
 
     let dense_data: Vec<[Vec<f64>; 2]> = vec![
         [vec![1.0, 0.0], vec![0.0, 1.0]],
@@ -40,61 +77,74 @@ pub fn xor_model() {
         [vec![0.0, 1.0], vec![0.0, 1.0]],
     ]; 
 
-    app.set_dense_data(dense_data);
-    app.run();
+    session.set_dense_data(dense_data);
+    session
 }
 
-pub fn dense_digit_model(mut app: GUI) {
-    let mut dense_data: Vec<[Vec<f64>; 2]> = vec![];
+pub fn dense_digit_model(mut app: GUI) -> Result<(), GuiError> {
+    let image = CoordinateImage::load("assets/img/mnist/mnist_8.png", ColorMode::Grayscale)?;
+    app.set_dataset(Arc::new(image));
+    app.run();
+    Ok(())
+}
 
-    let img = image::open("assets/img/mnist/mnist_8.png").unwrap();
+/// Fits a coordinate network to any image, e.g. `assets/img/sample1.png` in RGB or a kanji tile in grayscale.
+pub fn image_fitting_model(path: &str, color: ColorMode) -> Result<(), GuiError> {
+    let image = CoordinateImage::load(path, color)?;
 
-    let dims = img.dimensions();
+    let layers: Vec<Layer> = vec![
+        Layer::dense([2, 32], TanH),
+        Layer::dense([32, 32], TanH),
+        Layer::dense([32, image.channels()], Sigmoid),
+    ];
 
-    for y in 0..dims.1 {
-        for x in 0..dims.0 {
-            let pixel = img.get_pixel(x, y).0;
-            let intensity = (pixel[0] / 3) + (pixel[1] / 3) + (pixel[2] / 3);
-            let x_coord = x as f64 / (dims.0 - 1) as f64;
-            let y_coord = y as f64 / (dims.1 - 1) as f64;
-            let inputs = vec![x_coord, y_coord];
-            dense_data.push([inputs, vec![(intensity as f64 / 255.0)]]);
-        }
-    }
+    let nn = Network::new(layers, 0.01, 32, MSE);
+    let sections: Vec<Vec<Box<dyn WidgetView>>> = vec![
+        vec![Box::new(CostPlot::new())], 
+        vec![Box::new(OutputImg::new())], 
+    ];
 
-    app.set_dense_data(dense_data);
+    let mut app = GUI::new(nn, MSE)?;
+    app.set_epochs_per_second(5);
+    app.set_model_name("assets/models/image");
+    app.set_dataset(Arc::new(image));
+    app.set_sections(sections);
     app.run();
+    Ok(())
 }
 
-pub fn conv_digit_test() {
+pub fn conv_digit_test() -> Result<(), GuiError> {
 
-    let mut nn = Network::from_load("assets/models/cnnTest");
+    let mut session = conv_digit_session()?;
+    session.load()?;
+    let dataset = session.dataset.clone();
+    let labels = dataset.label_names().map_or(vec![], |labels| labels.to_vec());
+    let label = |class: Option<usize>| class.map_or("?".to_string(), |class| labels.get(class).cloned().unwrap_or_else(|| class.to_string()));
 
-    let num = 8;
-    let img = image::open(format!("assets/img/mnist/tMnist_{}.png", num)).unwrap();
+    for i in 0..dataset.len() {
+        let sample = dataset.get(i);
+        let outputs = session.forward(sample.input);
+        println!("{}: {}", label(evaluation::class(&sample.target)), label(evaluation::class(&outputs)));
+    }
 
-    let dims = img.dimensions();
+    let accuracy = session.accuracy(dataset.as_ref());
+    println!("Accuracy: {:.1}%", accuracy.overall().unwrap_or(0.0) * 100.0);
+    Ok(())
+}
 
-    let mut inputs = vec![];
+pub fn conv_digit_model() -> Result<(), GuiError> {
+    let sections: Vec<Vec<Box<dyn WidgetView>>> = vec![
+        vec![Box::new(ConvArch::new()), Box::new(PredictionGallery::new().with_wrong_first(true))], 
+        vec![Box::new(AugmentPreview::new()), Box::new(Accuracy::new()), Box::new(ConfusionMatrix::new())], 
+    ];
 
-    for y in 0..dims.1 {
-        let mut row = vec![];
-        for x in 0..dims.0 {
-            let pixel = img.get_pixel(x, y).0;
-            let intensity = (pixel[0] / 3) + (pixel[1] / 3) + (pixel[2] / 3);
-            row.push(intensity as f64);
-        }
-        inputs.push(row);
-    }
-    println!("{}: {:?}", num, nn.conv_forward(vec![inputs]).iter()
-        .map(|x| (*x * 1_000_000.0) as u32)
-        .enumerate()
-        .max_by_key(|&(_, value)| value)
-        .map(|(index, _)| index + 1).unwrap()
-    );
+    let mut app = GUI::from_session(conv_digit_session()?)?;
+    app.set_sections(sections);
+    app.run();
+    Ok(())
 }
 
-pub fn conv_digit_model() {
+pub fn conv_digit_session() -> Result<TrainingSession, GuiError> {
     let layers: Vec<Layer> = vec![
         Layer::conv(3, Valid, 1, ReLU),
         Layer::pool(2, 2),
@@ -103,46 +153,24 @@ pub fn conv_digit_model() {
         Layer::dense([32, 10], SoftMax),
     ];
 
-    let mut nn = Network::new(layers, 0.002, 6, CEL);
-    let sections: Vec<Vec<WidgetType>> = vec![
-        vec![ConvArch], 
-    ];
-
-    let mut app = GUI::new(nn);
-    app.set_sections(sections);
-    app.set_epochs_per_second(20);
-    app.set_cost_expiration(false, 20);
-    app.set_model_name("assets/models/cnnTest");
-
-    let mut conv_data: Vec<(Vec<Vec<Vec<f64>>>, Vec<f64>)> = vec![];
-
-    let max_nums = app.nn.get_nodes().last().unwrap().clone();
-    for i in 0..max_nums {
-        let img = image::open(format!("assets/img/mnist/mnist_{}.png", i)).unwrap();
+    let nn = Network::new(layers, 0.002, 6, CEL);
 
-        let dims = img.dimensions();
+    let mut session = TrainingSession::new(nn, CEL);
+    session.set_epochs_per_second(20);
+    session.set_model_name("assets/models/cnnTest");
+    session.set_output_interval(100);
 
-        let mut inputs = vec![];
+    let dataset = ImageFolder::new("assets/img/mnist")
+        .with_labels(LabelSource::Filename("mnist_{}".to_string()))
+        .load_conv()?;
 
-        for y in 0..dims.1 {
-            let mut row = vec![];
-            for x in 0..dims.0 {
-                let pixel = img.get_pixel(x, y).0;
-                let intensity = (pixel[0] / 3) + (pixel[1] / 3) + (pixel[2] / 3);
-                row.push(intensity as f64 / 255.0);
-            }
-            inputs.push(row);
-        }
-        let mut output = vec![0.0; max_nums];
-        output[i] = 1.0;
-        conv_data.push((vec![inputs], output));
-    }
-
-    app.set_conv_data(conv_data);
-    app.run();
+    session.set_dataset(Arc::new(dataset));
+    session.set_preprocessing(Pipeline::new(vec![Step::Resize { rows: 28, columns: 28 }]));
+    session.set_augmentation(Augmentation { shift: 2.0, rotation: 10.0, scale: 0.1, ..Augmentation::default() });
+    Ok(session)
 }
 
-pub fn softmax_test() {
+pub fn softmax_test() -> Result<(), GuiError> {
     let layers: Vec<Layer> = vec![
         Layer::conv(3, Same, 1, ReLU),
         Layer::dense([9, 6], TanH),
@@ -150,17 +178,16 @@ pub fn softmax_test() {
     ];
 
     let nn = Network::new(layers, 0.02, 2, CEL);
-    let sections: Vec<Vec<WidgetType>> = vec![
-        vec![CostPlot], 
+    let sections: Vec<Vec<Box<dyn WidgetView>>> = vec![
+        vec![Box::new(CostPlot::new()), Box::new(Accuracy::new())], 
     ];
 
-    let mut app = GUI::new(nn);
+    let mut app = GUI::new(nn, CEL)?;
     app.set_sections(sections);
     app.set_epochs_per_second(1);
-    app.set_cost_expiration(false, 20);
     app.set_model_name("assets/models/cnnTest");
 
-    let conv_data: Vec<(Vec<Vec<Vec<f64>>>, Vec<f64>)> = vec![
+    let conv_data: Vec<ConvSample> = vec![
         (
             vec![
                 vec![
@@ -195,40 +222,32 @@ pub fn softmax_test() {
 
     app.set_conv_data(conv_data);
     app.run();
+    Ok(())
 }
 
 pub fn sin_model(app: &mut GUI) {
+    let function = Function::new(func)
+        .with_range(-10.0, 10.0)
+        .with_density(Density::Grid(200));
 
-    app.x_range = [-10.0, 10.0];
-
-    let mut func_outputs = vec![];
-    let increment = 0.1;
-    let mut counter = app.x_range[0];
-
-    while counter < app.x_range[1] {
-        func_outputs.push([vec![counter.clone()], vec![func(&counter)]]);
-        counter += increment;
-    }
-
-    app.set_dense_data(func_outputs);
+    app.set_dataset(Arc::new(function.build()));
     app.run()
 }
 
-fn func(x: &f64) -> f64 {
-    x.sin()
+fn func(x: &[f64]) -> Vec<f64> {
+    vec![x[0].sin()]
 }
 
-fn upscale_img(nn: &mut Network, dims: [u32; 2]) {
-
-    // let output_image = ImageBuffer::from_fn(dims[0], dims[1], |x, y| {
-    //     let x_coord = x as f64 / (dims[0] - 1) as f64;
-    //     let y_coord = y as f64 / (dims[1] - 1) as f64;
-    //     let inputs = vec![x_coord, y_coord];
-    //     let pix = (nn.forward(inputs)[0] * 255.0) as u8;
-    //     Rgba([pix, pix, pix, 255]) // Varying colors in a gradient
-    // });
-            
-
-    // let _  = output_image.save("Output.png").unwrap();
-    // println!("Saved Image");
+/// Renders a trained coordinate network to `path`, at `dims` or the config's `[export]` size.
+/// e.g. `cargo run -- --config image.toml --headless --steps 500 --export big.png --size 1024x1024`
+pub fn upscale_img(session: &mut TrainingSession, path: &str, dims: Option<[usize; 2]>) -> Result<(), GuiError> {
+    let mut options = session.export.clone();
+    options.path = path.to_string();
+    if let Some([width, height]) = dims {
+        options.width = width;
+        options.height = height;
+    }
+    session.export_image(&options)?;
+    println!("Saved {}x{} {}", options.width, options.height, options.path);
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,334 @@
+use graphics::Context;
+use piston_window::*;
+
+use crate::widget::{draw_text, Rect, LINE_THICKNESS, OUTLINE};
+
+const GRID: [f32; 4] = [0.7, 0.7, 0.7, 0.25];
+/// Smallest value a log axis shows, zero costs are drawn at its floor.
+const LOG_FLOOR: f64 = 1e-12;
+
+/// A plotting area with numeric x and y axes, shared by the graph widgets.
+pub struct Axes {
+    /// Screen rectangle the data is drawn in, excluding the room for tick labels.
+    pub plot: Rect,
+    pub x_range: [f64; 2],
+    pub y_range: [f64; 2],
+    /// Draws the y-axis on a log10 scale.
+    pub log_y: bool,
+    pub text_size: f64
+}
+
+impl Axes {
+    /// Lays out axes inside `area`, leaving room on the left and bottom for the tick labels.
+    pub fn new(area: Rect, x_range: [f64; 2], y_range: [f64; 2]) -> Self {
+        let text_size = (area[3] * 0.05).clamp(8.0, 14.0);
+        let left = text_size * 4.5;
+        let bottom = text_size * 2.0;
+        let top = text_size;
+        let right = text_size;
+        Axes {
+            plot: [
+                area[0] + left,
+                area[1] + top,
+                (area[2] - left - right).max(1.0),
+                (area[3] - top - bottom).max(1.0)
+            ],
+            x_range: valid_range(x_range),
+            y_range: valid_range(y_range),
+            log_y: false,
+            text_size
+        }
+    }
+
+    /// Switches the y-axis to a log10 scale, non-positive bounds are raised to stay drawable.
+    pub fn with_log_y(mut self, log_y: bool) -> Self {
+        self.log_y = log_y;
+        if log_y {
+            self.y_range = log_range(self.y_range);
+        }
+        self
+    }
+
+    fn scale_y(&self, y: f64) -> f64 {
+        if self.log_y { y.max(LOG_FLOOR).log10() } else { y }
+    }
+
+    pub fn to_screen(&self, point: [f64; 2]) -> [f64; 2] {
+        let [x_min, x_max] = self.x_range;
+        let [y_min, y_max] = [self.scale_y(self.y_range[0]), self.scale_y(self.y_range[1])];
+        [
+            self.plot[0] + (point[0] - x_min) / (x_max - x_min) * self.plot[2],
+            self.plot[1] + self.plot[3] - (self.scale_y(point[1]) - y_min) / (y_max - y_min) * self.plot[3]
+        ]
+    }
+
+    /// Draws the axis lines, tick marks with their values, and a zero line when the y range crosses zero.
+    pub fn draw(&self, ctx: &Context, gl: &mut G2d, glyphs: &mut Glyphs) {
+        let [left, top, width, height] = self.plot;
+        let (right, floor) = (left + width, top + height);
+        let tick = self.text_size * 0.4;
+
+        let x_ticks = ticks(self.x_range, (width / (self.text_size * 6.0)).max(2.0) as usize);
+        let x_step = step(&x_ticks);
+        for x in x_ticks {
+            let [screen_x, _] = self.to_screen([x, self.y_range[0]]);
+            line_from_to(OUTLINE, LINE_THICKNESS, [screen_x, floor], [screen_x, floor + tick], ctx.transform, gl);
+            let label = tick_label(x, x_step);
+            let offset = label_width(&label, self.text_size) / 2.0;
+            draw_text(&label, OUTLINE, self.text_size, [screen_x - offset, floor + tick + self.text_size], ctx, gl, glyphs);
+        }
+
+        let y_ticks = match self.log_y {
+            true => log_ticks(self.y_range),
+            false => ticks(self.y_range, (height / (self.text_size * 3.0)).max(2.0) as usize),
+        };
+        let y_step = step(&y_ticks);
+        for y in y_ticks {
+            let y_step = if self.log_y { y } else { y_step };
+            let [_, screen_y] = self.to_screen([self.x_range[0], y]);
+            line_from_to(OUTLINE, LINE_THICKNESS, [left - tick, screen_y], [left, screen_y], ctx.transform, gl);
+            line_from_to(GRID, LINE_THICKNESS * 0.5, [left, screen_y], [right, screen_y], ctx.transform, gl);
+            let label = tick_label(y, y_step);
+            let offset = label_width(&label, self.text_size);
+            draw_text(&label, OUTLINE, self.text_size, [left - tick * 2.0 - offset, screen_y + self.text_size * 0.35], ctx, gl, glyphs);
+        }
+
+        if !self.log_y && self.y_range[0] < 0.0 && self.y_range[1] > 0.0 {
+            let [_, zero] = self.to_screen([self.x_range[0], 0.0]);
+            line_from_to(OUTLINE, LINE_THICKNESS * 0.5, [left, zero], [right, zero], ctx.transform, gl);
+        }
+
+        line_from_to(OUTLINE, LINE_THICKNESS * 1.5, [left, top], [left, floor], ctx.transform, gl);
+        line_from_to(OUTLINE, LINE_THICKNESS * 1.5, [left, floor], [right, floor], ctx.transform, gl);
+    }
+
+    /// Draws a segment between two data points, clipped to the plot area.
+    pub fn line(&self, color: [f32; 4], thickness: f64, from: [f64; 2], to: [f64; 2], ctx: &Context, gl: &mut G2d) {
+        let (from, to) = (self.to_screen(from), self.to_screen(to));
+        if let Some((from, to)) = clip(self.plot, from, to) {
+            line_from_to(color, thickness, from, to, ctx.transform, gl);
+        }
+    }
+
+    /// Coloured line samples with labels, stacked in the top right corner of the plot.
+    pub fn legend(&self, entries: &[([f32; 4], &str)], ctx: &Context, gl: &mut G2d, glyphs: &mut Glyphs) {
+        let widest = entries.iter().map(|(_, label)| label_width(label, self.text_size)).fold(0.0, f64::max);
+        let x = self.plot[0] + self.plot[2] - widest - self.text_size * 2.5;
+        for (row, (color, label)) in entries.iter().enumerate() {
+            let y = self.plot[1] + self.text_size * (1.2 + row as f64 * 1.4);
+            let middle = y - self.text_size * 0.35;
+            line_from_to(*color, LINE_THICKNESS * 2.0, [x, middle], [x + self.text_size, middle], ctx.transform, gl);
+            draw_text(label, OUTLINE, self.text_size, [x + self.text_size * 1.5, y], ctx, gl, glyphs);
+        }
+    }
+}
+
+/// Evenly spaced round values inside `range`, about `count` of them, on steps of 1, 2 or 5 times a power of ten.
+pub fn ticks(range: [f64; 2], count: usize) -> Vec<f64> {
+    let [min, max] = valid_range(range);
+    let raw = (max - min) / count.max(1) as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(magnitude * 10.0);
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// Powers of ten inside a positive `range`, with 2 and 5 times each when it spans less than two decades.
+pub fn log_ticks(range: [f64; 2]) -> Vec<f64> {
+    let [min, max] = log_range(range);
+    let (low, high) = (min.log10().floor() as i32, max.log10().ceil() as i32);
+    let factors: &[f64] = if high - low <= 2 { &[1.0, 2.0, 5.0] } else { &[1.0] };
+    (low..=high)
+        .flat_map(|power| factors.iter().map(move |factor| factor * 10f64.powi(power)))
+        .filter(|value| *value >= min && *value <= max)
+        .collect()
+}
+
+/// A tick value with just enough decimals to tell neighbouring ticks `step` apart.
+pub fn tick_label(value: f64, step: f64) -> String {
+    let value = if value.abs() < step * 1e-6 { 0.0 } else { value };
+    if value != 0.0 && (value.abs() >= 1e5 || value.abs() < 1e-3) {
+        return format!("{:.1e}", value);
+    }
+    let decimals = if step > 0.0 { (-step.log10().floor()).max(0.0) as usize } else { 0 };
+    format!("{:.*}", decimals, value)
+}
+
+/// Rough width of `text`, good enough to align labels without measuring glyphs.
+pub fn label_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.55
+}
+
+/// `[min, max]` of all finite values widened by `margin` of its span, `None` when there are none.
+pub fn data_range<I: IntoIterator<Item = f64>>(values: I, margin: f64) -> Option<[f64; 2]> {
+    let [min, max] = values.into_iter()
+        .filter(|value| value.is_finite())
+        .fold([f64::INFINITY, f64::NEG_INFINITY], |[min, max], value| [min.min(value), max.max(value)]);
+    if min > max {
+        return None;
+    }
+    let pad = (max - min) * margin;
+    Some(valid_range([min - pad, max + pad]))
+}
+
+/// A range with a non-zero, finite span.
+fn valid_range([min, max]: [f64; 2]) -> [f64; 2] {
+    if !min.is_finite() || !max.is_finite() {
+        [-1.0, 1.0]
+    } else if max > min {
+        [min, max]
+    } else if max < min {
+        [max, min]
+    } else {
+        let pad = if min == 0.0 { 1.0 } else { min.abs() * 0.1 };
+        [min - pad, max + pad]
+    }
+}
+
+fn log_range([min, max]: [f64; 2]) -> [f64; 2] {
+    let max = if max > LOG_FLOOR { max } else { 1.0 };
+    let min = if min > LOG_FLOOR && min < max { min } else { (max * 1e-3).max(LOG_FLOOR) };
+    [min, max]
+}
+
+fn step(ticks: &[f64]) -> f64 {
+    match ticks {
+        [first, second, ..] => second - first,
+        _ => 1.0,
+    }
+}
+
+/// Liang-Barsky clipping of a screen segment to `rect`.
+fn clip(rect: Rect, from: [f64; 2], to: [f64; 2]) -> Option<([f64; 2], [f64; 2])> {
+    let delta = [to[0] - from[0], to[1] - from[1]];
+    let (mut start, mut end) = (0.0_f64, 1.0_f64);
+    let edges = [
+        (-delta[0], from[0] - rect[0]),
+        (delta[0], rect[0] + rect[2] - from[0]),
+        (-delta[1], from[1] - rect[1]),
+        (delta[1], rect[1] + rect[3] - from[1])
+    ];
+    for (p, q) in edges {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                start = start.max(t);
+            } else {
+                end = end.min(t);
+            }
+        }
+    }
+    if start > end || !start.is_finite() || !end.is_finite() {
+        return None;
+    }
+    let at = |t: f64| [from[0] + delta[0] * t, from[1] + delta[1] * t];
+    Some((at(start), at(end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn ticks_on_round_steps() {
+        assert_close(&ticks([0.0, 10.0], 5), &[0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_close(&ticks([0.0, 1.0], 4), &[0.0, 0.5, 1.0]);
+        assert_close(&ticks([-0.3, 0.7], 5), &[-0.2, 0.0, 0.2, 0.4, 0.6]);
+    }
+
+    #[test]
+    fn ticks_of_bad_ranges() {
+        assert_close(&ticks([10.0, 0.0], 5), &ticks([0.0, 10.0], 5));
+        assert_close(&ticks([3.0, 3.0], 5), &[2.8, 3.0, 3.2]);
+        assert_close(&ticks([f64::NAN, 1.0], 4), &[-1.0, -0.5, 0.0, 0.5, 1.0]);
+        assert!(!ticks([0.0, 1.0], 0).is_empty());
+    }
+
+    #[test]
+    fn tick_labels() {
+        assert_eq!(tick_label(2.0, 2.0), "2");
+        assert_eq!(tick_label(0.5, 0.5), "0.5");
+        assert_eq!(tick_label(0.25, 0.05), "0.25");
+        assert_eq!(tick_label(1e-17, 0.1), "0.0");
+        assert_eq!(tick_label(250000.0, 50000.0), "2.5e5");
+        assert_eq!(tick_label(0.0001, 0.0001), "1.0e-4");
+        assert_eq!(tick_label(3.0, 0.0), "3");
+    }
+
+    #[test]
+    fn data_range_skips_non_finite() {
+        assert_eq!(data_range([1.0, f64::NAN, 3.0, f64::INFINITY], 0.5), Some([0.0, 4.0]));
+        assert_eq!(data_range([f64::NAN, f64::NEG_INFINITY], 0.1), None);
+        assert_eq!(data_range(std::iter::empty(), 0.1), None);
+        assert_close(&data_range([2.0, 2.0], 0.1).unwrap(), &[1.8, 2.2]);
+    }
+
+    #[test]
+    fn valid_ranges() {
+        assert_eq!(valid_range([1.0, 3.0]), [1.0, 3.0]);
+        assert_eq!(valid_range([3.0, 1.0]), [1.0, 3.0]);
+        assert_eq!(valid_range([0.0, 0.0]), [-1.0, 1.0]);
+        assert_close(&valid_range([-5.0, -5.0]), &[-5.5, -4.5]);
+        assert_eq!(valid_range([f64::NAN, 1.0]), [-1.0, 1.0]);
+        assert_eq!(valid_range([f64::NEG_INFINITY, 0.0]), [-1.0, 1.0]);
+    }
+
+    #[test]
+    fn clip_inside_and_crossing() {
+        let rect = [0.0, 0.0, 10.0, 10.0];
+        assert_eq!(clip(rect, [1.0, 1.0], [5.0, 5.0]), Some(([1.0, 1.0], [5.0, 5.0])));
+        assert_eq!(clip(rect, [-5.0, 5.0], [15.0, 5.0]), Some(([0.0, 5.0], [10.0, 5.0])));
+        assert_eq!(clip(rect, [5.0, 15.0], [5.0, 5.0]), Some(([5.0, 10.0], [5.0, 5.0])));
+        assert_eq!(clip(rect, [-5.0, 5.0], [5.0, -5.0]), Some(([0.0, 0.0], [0.0, 0.0])));
+    }
+
+    #[test]
+    fn clip_outside() {
+        let rect = [0.0, 0.0, 10.0, 10.0];
+        assert_eq!(clip(rect, [-5.0, -5.0], [-1.0, -1.0]), None);
+        assert_eq!(clip(rect, [20.0, 0.0], [30.0, 10.0]), None);
+        assert_eq!(clip(rect, [-5.0, 4.0], [4.0, -5.0]), None);
+        assert_eq!(clip(rect, [-1.0, -1.0], [-1.0, -1.0]), None);
+    }
+
+    #[test]
+    fn clip_parallel_to_edge() {
+        let rect = [0.0, 0.0, 10.0, 10.0];
+        assert_eq!(clip(rect, [-1.0, 0.0], [-1.0, 10.0]), None);
+        assert_eq!(clip(rect, [0.0, 11.0], [10.0, 11.0]), None);
+        assert_eq!(clip(rect, [0.0, -5.0], [0.0, 15.0]), Some(([0.0, 0.0], [0.0, 10.0])));
+        assert_eq!(clip(rect, [-5.0, 10.0], [5.0, 10.0]), Some(([0.0, 10.0], [5.0, 10.0])));
+    }
+
+    #[test]
+    fn log_ticks_by_span() {
+        assert_close(&log_ticks([1.0, 1000.0]), &[1.0, 10.0, 100.0, 1000.0]);
+        assert_close(&log_ticks([0.1, 1.0]), &[0.1, 0.2, 0.5, 1.0]);
+        assert_close(&log_ticks([3.0, 40.0]), &[5.0, 10.0, 20.0]);
+    }
+
+    #[test]
+    fn log_ticks_of_non_positive_ranges() {
+        assert_eq!(log_range([-5.0, 10.0]), [0.01, 10.0]);
+        assert_eq!(log_range([5.0, 2.0]), [0.002, 2.0]);
+        assert_eq!(log_range([0.0, 0.0]), [1e-3, 1.0]);
+        assert!(log_ticks([0.0, 0.0]).iter().all(|tick| *tick > 0.0 && *tick <= 1.0));
+        assert!(!log_ticks([-1.0, 0.0]).is_empty());
+    }
+}
@@ -0,0 +1,433 @@
+extern crate image;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use image::*;
+use ml_library::loss_function::LossType;
+use ml_library::network::{Network, NetworkType};
+
+use crate::dataset::coordinate::coordinate;
+use crate::dataset::{self, Augmentation, Augmenter, ConvDataset, ConvSample, Dataset, DenseDataset, Input, Pipeline, Preprocessed, Sampler, Sampling, Subset};
+use crate::error::GuiError;
+use crate::evaluation;
+use crate::export::ExportOptions;
+use crate::snapshot::NetworkSnapshot;
+
+/// Augmented examples included in every snapshot for `AugmentPreview`.
+const PREVIEW_SAMPLES: usize = 8;
+
+pub struct TrainingSession {
+    pub nn: Network,
+    pub dataset: Arc<dyn Dataset>,
+    /// Held out samples that are only evaluated, never trained on.
+    pub validation: Option<Arc<dyn Dataset>>,
+    /// Evaluate the validation set every this many epochs.
+    pub validation_interval: usize,
+    /// `(epochs, cost)` of the most recent validation run since the last reset.
+    pub last_validation: Option<(usize, f64)>,
+    /// Network output for every validation sample from the most recent validation run.
+    pub validation_outputs: Vec<Vec<f64>>,
+    /// Compute the output for every training sample every this many epochs, it feeds the output
+    /// and evaluation widgets and is the slowest part of a snapshot on large datasets.
+    pub output_interval: usize,
+    /// The outputs are due in the next snapshot, set on interval boundaries and whenever the network or data changes.
+    outputs_due: bool,
+    /// Loss used for validation, the one the network was built with.
+    pub loss: LossType,
+    /// Applied to every input before it reaches the network, during training and inference.
+    pub preprocessing: Arc<Pipeline>,
+    /// Chooses which samples, in which order, every step trains on.
+    pub sampler: Sampler,
+    /// Distorts conv samples every epoch, the dataset itself is never changed.
+    pub augmenter: Option<Augmenter>,
+    /// Resolution, path and colormap of the `U` key and `export_image` renders.
+    pub export: ExportOptions,
+    pub epochs_per_second: usize,
+    pub epochs: usize,
+    pub model_name: String,
+    pub will_train: bool
+}
+
+impl TrainingSession {
+    /// `loss` must be the loss `nn` was built with, validation costs are computed with it.
+    pub fn new(nn: Network, loss: LossType) -> Self {
+        TrainingSession {
+            nn,
+            dataset: Arc::new(DenseDataset::new(vec![])),
+            validation: None,
+            validation_interval: 1,
+            last_validation: None,
+            validation_outputs: vec![],
+            output_interval: 1,
+            outputs_due: true,
+            loss,
+            preprocessing: Arc::new(Pipeline::default()),
+            sampler: Sampler::unseeded(Sampling::Sequential),
+            augmenter: None,
+            export: ExportOptions::default(),
+            epochs_per_second: 1,
+            epochs: 0,
+            model_name: "Model".to_string(),
+            will_train: true
+        }
+    }
+
+    pub fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        self.dataset = dataset;
+        self.sampler.clear();
+        self.outputs_due = true;
+    }
+
+    pub fn set_dense_data(&mut self, dense_data: Vec<[Vec<f64>; 2]>) {
+        self.set_dataset(Arc::new(DenseDataset::new(dense_data)));
+    }
+
+    pub fn set_conv_data(&mut self, conv_data: Vec<ConvSample>) {
+        self.set_dataset(Arc::new(ConvDataset::new(conv_data)));
+    }
+
+    pub fn set_validation(&mut self, validation: Arc<dyn Dataset>) {
+        self.validation = Some(validation);
+    }
+
+    /// Holds out `fraction` of the current dataset for validation and trains on the rest.
+    pub fn split_validation(&mut self, fraction: f64) {
+        let (training, validation) = dataset::split_validation(self.dataset.clone(), fraction);
+        self.dataset = training;
+        self.validation = Some(validation);
+        self.sampler.clear();
+        self.outputs_due = true;
+    }
+
+    pub fn set_validation_interval(&mut self, epochs: usize) {
+        self.validation_interval = epochs.max(1);
+    }
+
+    pub fn set_output_interval(&mut self, epochs: usize) {
+        self.output_interval = epochs.max(1);
+    }
+
+    /// Fits the pipeline's statistics on the training data and applies it from now on.
+    pub fn set_preprocessing(&mut self, mut pipeline: Pipeline) {
+        pipeline.fit(self.dataset.as_ref());
+        self.preprocessing = Arc::new(pipeline);
+        self.outputs_due = true;
+    }
+
+    /// Switches to the pipeline saved with the model, if there is one.
+    pub fn load_preprocessing(&mut self) -> Result<(), GuiError> {
+        if let Some(pipeline) = Pipeline::load(&self.model_name)? {
+            self.preprocessing = Arc::new(pipeline);
+        }
+        Ok(())
+    }
+
+    /// Switches the sampling strategy, `None` picks a seed from the current time.
+    pub fn set_sampling(&mut self, sampling: Sampling, seed: Option<u64>) {
+        self.sampler = match seed {
+            Some(seed) => Sampler::new(sampling, seed),
+            None => Sampler::unseeded(sampling),
+        };
+    }
+
+    /// Trains on freshly distorted copies of the conv samples every epoch, seeded from the sampler.
+    pub fn set_augmentation(&mut self, augmentation: Augmentation) {
+        self.augmenter = if augmentation.is_identity() {
+            None
+        } else {
+            Some(Augmenter::new(augmentation, self.sampler.seed))
+        };
+    }
+
+    pub fn set_model_name(&mut self, name: &str) {
+        self.model_name = name.to_string();
+    }
+
+    pub fn set_epochs_per_second(&mut self, epochs: usize) {
+        self.epochs_per_second = epochs;
+    }
+
+    /// Trains the network for `epochs_per_second` epochs, leaving the resulting cost in `nn.cost`.
+    /// Does nothing while training is paused.
+    pub fn step(&mut self) -> bool {
+        if !self.will_train {
+            return false;
+        }
+        if self.sampler.per_epoch() || self.augmenter.is_some() {
+            for _ in 0..self.epochs_per_second {
+                self.train_on_sample(1);
+            }
+        } else {
+            self.train_on_sample(self.epochs_per_second);
+        }
+        let previous_epochs = self.epochs;
+        self.epochs += self.epochs_per_second;
+
+        if self.epochs / self.output_interval > previous_epochs / self.output_interval {
+            self.outputs_due = true;
+        }
+        if self.epochs / self.validation_interval > previous_epochs / self.validation_interval {
+            if let Some(cost) = self.validate() {
+                self.last_validation = Some((self.epochs, cost));
+            }
+        }
+        true
+    }
+
+    /// Draws samples from the sampler and trains on them for `epochs` epochs.
+    fn train_on_sample(&mut self, epochs: usize) {
+        let mut samples: Arc<dyn Dataset> = match self.sampler.sampling {
+            Sampling::Sequential => self.dataset.clone(),
+            _ => Arc::new(Subset::new(self.dataset.clone(), self.sampler.indices(self.dataset.as_ref()))),
+        };
+        if let Some(augmenter) = self.augmenter.as_mut() {
+            if !samples.is_empty() && matches!(samples.get(0).input, Input::Conv(_)) {
+                let augmented = samples.conv_samples().into_iter()
+                    .map(|(input, target)| match augmenter.augment(Input::Conv(input)) {
+                        Input::Conv(channels) => (channels, target),
+                        Input::Dense(values) => (vec![vec![values]], target),
+                    })
+                    .collect();
+                samples = Arc::new(ConvDataset::new(augmented));
+            }
+        }
+        if !self.preprocessing.is_empty() {
+            samples = Arc::new(Preprocessed::new(samples, self.preprocessing.clone()));
+        }
+        if self.nn.network_type == NetworkType::FCN {
+            self.nn.dense_train(samples.dense_samples(), epochs);
+        } else {
+            self.nn.conv_train(samples.conv_samples(), epochs)
+        }
+    }
+
+    /// Augmented copies of the first `count` samples, different on every call.
+    pub fn augment_preview(&self, count: usize) -> Vec<Vec<Vec<Vec<f64>>>> {
+        let augmentation = match &self.augmenter {
+            Some(augmenter) => augmenter.augmentation,
+            None => return vec![],
+        };
+        let mut preview = Augmenter::new(augmentation, self.sampler.seed.wrapping_add(self.epochs as u64));
+        (0..count.min(self.dataset.len()))
+            .filter_map(|i| match preview.augment(self.dataset.get(i).input) {
+                Input::Conv(channels) => Some(channels),
+                Input::Dense(_) => None,
+            })
+            .collect()
+    }
+
+    /// Mean loss of the network over every sample of `dataset`.
+    pub fn evaluate(&mut self, dataset: &dyn Dataset) -> f64 {
+        let mut results = vec![];
+        for i in 0..dataset.len() {
+            let sample = dataset.get(i);
+            results.push((self.forward(sample.input), sample.target));
+        }
+        evaluation::mean_loss(&self.loss, &results)
+    }
+
+    /// Argmax accuracy of the network on `dataset`, per target class.
+    pub fn accuracy(&mut self, dataset: &dyn Dataset) -> evaluation::ClassAccuracy {
+        let mut accuracy = evaluation::ClassAccuracy::default();
+        for i in 0..dataset.len() {
+            let sample = dataset.get(i);
+            accuracy.add(&self.forward(sample.input), &sample.target);
+        }
+        accuracy
+    }
+
+    /// Mean validation loss, keeping the outputs for `NetworkSnapshot::validation_outputs`.
+    pub fn validate(&mut self) -> Option<f64> {
+        let validation = self.validation.clone()?;
+        let mut results = vec![];
+        for i in 0..validation.len() {
+            let sample = validation.get(i);
+            results.push((self.forward(sample.input), sample.target));
+        }
+        let cost = evaluation::mean_loss(&self.loss, &results);
+        self.validation_outputs = results.into_iter().map(|(outputs, _)| outputs).collect();
+        Some(cost)
+    }
+
+    /// Runs `steps` training steps without a window, e.g. for CI or remote machines.
+    pub fn train(&mut self, steps: usize) -> f64 {
+        for _ in 0..steps {
+            self.step();
+        }
+        self.nn.cost
+    }
+
+    pub fn pause(&mut self) {
+        self.will_train = false;
+    }
+
+    pub fn resume(&mut self) {
+        self.will_train = true;
+    }
+
+    pub fn toggle_training(&mut self) {
+        self.will_train = !self.will_train;
+    }
+
+    pub fn reset(&mut self) {
+        self.epochs = 0;
+        self.last_validation = None;
+        self.validation_outputs = vec![];
+        self.sampler.reset();
+        if let Some(augmenter) = self.augmenter.as_mut() {
+            augmenter.reset();
+        }
+        self.nn.reset();
+        self.outputs_due = true;
+    }
+
+    pub fn save(&mut self) -> Result<(), GuiError> {
+        if let Some(dir) = Path::new(&self.model_name).parent() {
+            if !dir.as_os_str().is_empty() && !dir.is_dir() {
+                return Err(GuiError::Model(format!("cannot save {}, {} is not a directory", self.model_name, dir.display())));
+            }
+        }
+        self.nn.save_model(&self.model_name);
+        // A pipeline left over from an earlier save would be applied to this model on load
+        match self.preprocessing.is_empty() {
+            true => Pipeline::remove(&self.model_name),
+            false => self.preprocessing.save(&self.model_name),
+        }
+    }
+
+    pub fn load(&mut self) -> Result<(), GuiError> {
+        Self::check_model(&self.model_name)?;
+        self.reset();
+        self.nn.load_model(&self.model_name);
+        self.load_preprocessing()
+    }
+
+    /// Models are stored by `Network::save_model` as `<name>.json`.
+    pub fn check_model(name: &str) -> Result<(), GuiError> {
+        let file = format!("{}.json", name);
+        if Path::new(&file).is_file() {
+            Ok(())
+        } else {
+            Err(GuiError::Model(format!("no saved model at {}", file)))
+        }
+    }
+
+    /// Runs one raw input through the preprocessing pipeline and the network.
+    pub fn forward(&mut self, input: Input) -> Vec<f64> {
+        match self.preprocessing.apply(input) {
+            Input::Dense(values) => self.nn.dense_forward(values),
+            Input::Conv(channels) => self.nn.conv_forward(channels),
+        }
+    }
+
+    pub fn get_network_outputs(&mut self) -> Vec<Vec<f64>> {
+        let mut outputs = vec![];
+        for i in 0..self.dataset.len() {
+            let sample = self.dataset.get(i);
+            outputs.push(self.forward(sample.input));
+        }
+        outputs
+    }
+
+    /// Outputs are only included every `output_interval` epochs and after commands, which may have changed the network.
+    pub fn snapshot(&mut self, trained: bool) -> NetworkSnapshot {
+        let outputs = if self.outputs_due || !trained {
+            self.outputs_due = false;
+            self.get_network_outputs()
+        } else {
+            vec![]
+        };
+        let validated = trained && self.last_validation.is_some_and(|(epochs, _)| epochs == self.epochs);
+        NetworkSnapshot {
+            cost: self.nn.cost,
+            epochs: self.epochs,
+            epochs_per_second: self.epochs_per_second,
+            learning_rate: self.nn.learning_rate,
+            will_train: self.will_train,
+            trained,
+            sampling: self.sampler.sampling,
+            seed: self.sampler.seed,
+            augmented: self.augment_preview(PREVIEW_SAMPLES),
+            validation_cost: self.last_validation.map(|(_, cost)| cost),
+            validated,
+            validation_outputs: if validated { self.validation_outputs.clone() } else { vec![] },
+            layers: NetworkSnapshot::layers_from_network(&mut self.nn),
+            outputs
+        }
+    }
+
+    pub fn print_outputs(&mut self) {
+        for i in 0..self.dataset.len() {
+            let sample = self.dataset.get(i);
+            let outputs = self.forward(sample.input.clone());
+            println!("------------------------\n{i}) Input: {:?} Output: {:?} Target: {:?}",
+                sample.input.flatten(),
+                outputs,
+                sample.target
+            );
+        }
+    }
+
+    /// Size of the image a coordinate network is fitting, see `CoordinateImage`.
+    pub fn image_size(&self) -> Result<[usize; 2], GuiError> {
+        self.dataset.image_size()
+            .ok_or(GuiError::Data("the dataset is not a coordinate image".to_string()))
+    }
+
+    /// `[row][column][channel]` output of a coordinate network for every pixel of a `width` by `height` image.
+    pub fn get_dense_network_img(&mut self, width: usize, height: usize) -> Vec<Vec<Vec<f64>>> {
+        let mut new_image = vec![vec![vec![]; width]; height];
+
+        for (y, row) in new_image.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let inputs = coordinate(x, y, width, height);
+                *pixel = self.forward(Input::Dense(inputs));
+            }
+        }
+        new_image
+    }
+
+    /// `[row][column][channel]` targets of the coordinate image being fitted.
+    pub fn get_expected_img(&self) -> Result<Vec<Vec<Vec<f64>>>, GuiError> {
+        let [width, height] = self.image_size()?;
+        Ok((0..height)
+            .map(|y| (0..width).map(|x| self.dataset.get(y * width + x).target).collect())
+            .collect())
+    }
+
+    /// Writes the network's image at the dataset's size to `Output.png`.
+    pub fn save_img(&mut self) -> Result<(), GuiError> {
+        let [width, height] = self.image_size()?;
+        let options = ExportOptions::new("Output.png", width, height).with_colormap(self.export.colormap);
+        self.export_image(&options)
+    }
+
+    pub fn set_export(&mut self, options: ExportOptions) {
+        self.export = options;
+    }
+
+    /// Renders a coordinate network at any resolution, e.g. 512x512 from a 28x28 training image.
+    /// Works without a dataset, so a loaded model can be exported headless.
+    pub fn export_image(&mut self, options: &ExportOptions) -> Result<(), GuiError> {
+        if options.width == 0 || options.height == 0 {
+            return Err(GuiError::Data(format!("cannot export a {}x{} image", options.width, options.height)));
+        }
+        if let Some(dir) = Path::new(&options.path).parent() {
+            if !dir.as_os_str().is_empty() && !dir.is_dir() {
+                return Err(GuiError::Data(format!("cannot save {}, {} is not a directory", options.path, dir.display())));
+            }
+        }
+        let image = self.get_dense_network_img(options.width, options.height);
+
+        let mut img = RgbImage::new(options.width as u32, options.height as u32);
+
+        for (y, row) in image.iter().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                img.put_pixel(x as u32, y as u32, options.colormap.pixel(pixel));
+            }
+        }
+
+        img.save(&options.path).map_err(|e| GuiError::image(&options.path, e))
+    }
+}
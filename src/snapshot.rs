@@ -0,0 +1,133 @@
+use ml_library::network::Network;
+
+use crate::dataset::Sampling;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayerKind {
+    Convolutional,
+    Pooling,
+    Dense
+}
+
+/// State of a single layer, in network order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerSnapshot {
+    pub kind: LayerKind,
+    pub activation: Option<String>,
+    /// `[input][output]`, empty for convolutional and pooling layers.
+    pub dense_weights: Vec<Vec<f64>>,
+    /// `[channel][row][column]`, empty for dense and pooling layers.
+    pub conv_kernels: Vec<Vec<Vec<f64>>>,
+    pub biases: Vec<f64>,
+    /// Input and output node counts of a dense layer.
+    pub nodes: [usize; 2],
+    /// `[channel][row][column]` output of a convolutional or pooling layer for the first sample.
+    pub feature_maps: Vec<Vec<Vec<f64>>>
+}
+
+/// Everything the widgets need to draw one training step, built once per tick.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkSnapshot {
+    pub cost: f64,
+    pub epochs: usize,
+    pub epochs_per_second: usize,
+    pub learning_rate: f64,
+    pub will_train: bool,
+    pub trained: bool,
+    pub sampling: Sampling,
+    pub seed: u64,
+    /// `[sample][channel][row][column]` augmented training inputs, empty without augmentation.
+    pub augmented: Vec<Vec<Vec<Vec<f64>>>>,
+    /// Cost of the most recent validation run, if the session has a validation set.
+    pub validation_cost: Option<f64>,
+    /// The validation set was evaluated during this step.
+    pub validated: bool,
+    /// Network output for every validation sample, only filled on steps where it was evaluated.
+    pub validation_outputs: Vec<Vec<f64>>,
+    pub layers: Vec<LayerSnapshot>,
+    /// Network output for every sample of the training data, only filled every `output_interval` epochs.
+    pub outputs: Vec<Vec<f64>>
+}
+
+impl NetworkSnapshot {
+    pub fn empty() -> Self {
+        NetworkSnapshot {
+            cost: 0.0,
+            epochs: 0,
+            epochs_per_second: 0,
+            learning_rate: 0.0,
+            will_train: false,
+            trained: false,
+            sampling: Sampling::Sequential,
+            seed: 0,
+            augmented: vec![],
+            validation_cost: None,
+            validated: false,
+            validation_outputs: vec![],
+            layers: vec![],
+            outputs: vec![]
+        }
+    }
+
+    pub fn layers_from_network(nn: &mut Network) -> Vec<LayerSnapshot> {
+        let (conv_weights, dense_weights) = nn.get_weights();
+        let (conv_biases, dense_biases) = nn.get_biases();
+        let nodes = nn.get_nodes();
+        let mut conv_outputs = nn.get_conv_outputs();
+
+        let mut layers = vec![];
+        for (j, kernels) in conv_weights.into_iter().enumerate() {
+            let kind = if kernels.iter().all(|kernel| kernel.is_empty()) {
+                LayerKind::Pooling
+            } else {
+                LayerKind::Convolutional
+            };
+            layers.push(LayerSnapshot {
+                kind,
+                activation: None,
+                dense_weights: vec![],
+                conv_kernels: kernels,
+                biases: conv_biases.get(j).map(|bias| vec![*bias]).unwrap_or_default(),
+                nodes: [0, 0],
+                feature_maps: if j < conv_outputs.len() { std::mem::take(&mut conv_outputs[j]) } else { vec![] }
+            });
+        }
+
+        for (k, weights) in dense_weights.into_iter().enumerate() {
+            layers.push(LayerSnapshot {
+                kind: LayerKind::Dense,
+                activation: None,
+                dense_weights: weights,
+                conv_kernels: vec![],
+                biases: dense_biases.get(k).cloned().unwrap_or_default(),
+                nodes: [nodes.get(k).cloned().unwrap_or(0), nodes.get(k + 1).cloned().unwrap_or(0)],
+                feature_maps: vec![]
+            });
+        }
+
+        for (i, layer) in layers.iter_mut().enumerate() {
+            layer.activation = nn.layers.get(i).map(|l| format!("{:?}", l.activation));
+        }
+        layers
+    }
+
+    pub fn dense_layers(&self) -> impl Iterator<Item = &LayerSnapshot> {
+        self.layers.iter().filter(|layer| layer.kind == LayerKind::Dense)
+    }
+
+    pub fn conv_layers(&self) -> impl Iterator<Item = &LayerSnapshot> {
+        self.layers.iter().filter(|layer| layer.kind != LayerKind::Dense)
+    }
+
+    /// Node count of every dense layer boundary, starting with the input layer.
+    pub fn dense_nodes(&self) -> Vec<usize> {
+        let mut nodes = vec![];
+        for layer in self.dense_layers() {
+            if nodes.is_empty() {
+                nodes.push(layer.nodes[0]);
+            }
+            nodes.push(layer.nodes[1]);
+        }
+        nodes
+    }
+}
@@ -0,0 +1,209 @@
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::GuiError;
+use crate::session::TrainingSession;
+use crate::snapshot::NetworkSnapshot;
+
+/// Events the worker may get ahead of the window by before it waits, so a slow window
+/// holds back training instead of queueing snapshots without bound.
+const EVENT_CAPACITY: usize = 4;
+
+pub enum TrainerCommand {
+    Pause,
+    Resume,
+    ToggleTraining,
+    Reset,
+    Save,
+    Load,
+    SaveImage,
+    /// Render the coordinate network with the session's export options.
+    ExportImage,
+    PrintOutputs,
+    Shutdown
+}
+
+pub enum TrainerEvent {
+    Snapshot(NetworkSnapshot),
+    Status(String),
+    /// The session started over after a reset or a load, snapshots sent before it belong to the old run.
+    Restarted(Option<String>),
+    Error(GuiError)
+}
+
+/// Owns a `TrainingSession` on a worker thread so the window never waits on training.
+pub struct Trainer {
+    commands: Sender<TrainerCommand>,
+    events: Receiver<TrainerEvent>,
+    handle: Option<JoinHandle<TrainingSession>>,
+    pub latest: Option<NetworkSnapshot>,
+    /// Set when the session started over, until the window resets its widgets with `take_restarted`.
+    restarted: bool,
+    /// Session handed back by a worker that stopped on its own.
+    stopped: Option<TrainingSession>,
+    /// Outcome of the most recent command, until the window takes it to show in the sidebar.
+    pub status: Option<Result<String, GuiError>>
+}
+
+impl Trainer {
+    pub fn spawn(session: TrainingSession, ticks_per_second: u64) -> Self {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::sync_channel(EVENT_CAPACITY);
+        let tick = Duration::from_micros(1_000_000 / ticks_per_second.max(1));
+
+        let handle = thread::spawn(move || Self::work(session, command_rx, event_tx, tick));
+
+        Trainer {
+            commands: command_tx,
+            events: event_rx,
+            handle: Some(handle),
+            latest: None,
+            restarted: false,
+            stopped: None,
+            status: None
+        }
+    }
+
+    fn work(
+        mut session: TrainingSession,
+        commands: Receiver<TrainerCommand>,
+        events: SyncSender<TrainerEvent>,
+        tick: Duration
+    ) -> TrainingSession {
+        let _ = events.send(TrainerEvent::Snapshot(session.snapshot(false)));
+        loop {
+            let started = Instant::now();
+            let mut changed = false;
+
+            loop {
+                // Block while paused so an idle session does not spin the worker.
+                let command = if session.will_train {
+                    match commands.try_recv() {
+                        Ok(command) => command,
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => return session,
+                    }
+                } else if changed {
+                    break;
+                } else {
+                    match commands.recv() {
+                        Ok(command) => command,
+                        Err(_) => return session,
+                    }
+                };
+
+                if let TrainerCommand::Shutdown = command {
+                    return session;
+                }
+                let restart = matches!(command, TrainerCommand::Reset | TrainerCommand::Load);
+                let event = match Self::apply(&mut session, command) {
+                    Ok(message) if restart => Some(TrainerEvent::Restarted(message)),
+                    Ok(Some(message)) => Some(TrainerEvent::Status(message)),
+                    Ok(None) => None,
+                    Err(e) => Some(TrainerEvent::Error(e)),
+                };
+                if let Some(event) = event {
+                    let _ = events.send(event);
+                }
+                changed = true;
+            }
+
+            let trained = session.step();
+            if (trained || changed) && events.send(TrainerEvent::Snapshot(session.snapshot(trained))).is_err() {
+                return session;
+            }
+
+            if let Some(remaining) = tick.checked_sub(started.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+    }
+
+    fn apply(session: &mut TrainingSession, command: TrainerCommand) -> Result<Option<String>, GuiError> {
+        match command {
+            TrainerCommand::Pause => session.pause(),
+            TrainerCommand::Resume => session.resume(),
+            TrainerCommand::ToggleTraining => session.toggle_training(),
+            TrainerCommand::Reset => session.reset(),
+            TrainerCommand::Save => {
+                session.save()?;
+                return Ok(Some(format!("{} Saved Succesfully!", session.model_name)));
+            },
+            TrainerCommand::Load => {
+                session.load()?;
+                return Ok(Some(format!("{} Loaded Succesfully!", session.model_name)));
+            },
+            TrainerCommand::SaveImage => {
+                session.save_img()?;
+                return Ok(Some("Saved Output.png".to_string()));
+            },
+            TrainerCommand::ExportImage => {
+                let options = session.export.clone();
+                session.export_image(&options)?;
+                return Ok(Some(format!("Saved {}x{} {}", options.width, options.height, options.path)));
+            },
+            TrainerCommand::PrintOutputs => session.print_outputs(),
+            TrainerCommand::Shutdown => (),
+        }
+        Ok(None)
+    }
+
+    pub fn send(&self, command: TrainerCommand) {
+        let _ = self.commands.send(command);
+    }
+
+    /// Drains every snapshot published since the last poll, oldest first, and keeps the newest as `latest`.
+    /// Snapshots from before a reset or successful load are dropped, a stopped worker is reported in `status`.
+    pub fn poll(&mut self) -> Vec<NetworkSnapshot> {
+        let mut snapshots = vec![];
+        for event in self.events.try_iter() {
+            match event {
+                TrainerEvent::Snapshot(snapshot) => snapshots.push(snapshot),
+                TrainerEvent::Status(message) => self.status = Some(Ok(message)),
+                TrainerEvent::Restarted(message) => {
+                    snapshots.clear();
+                    self.restarted = true;
+                    if let Some(message) = message {
+                        self.status = Some(Ok(message));
+                    }
+                },
+                TrainerEvent::Error(e) => self.status = Some(Err(e)),
+            }
+        }
+        if let Some(snapshot) = snapshots.last() {
+            self.latest = Some(snapshot.clone());
+        }
+
+        if self.handle.as_ref().is_some_and(JoinHandle::is_finished) {
+            match self.handle.take().map(JoinHandle::join) {
+                Some(Ok(session)) => {
+                    self.stopped = Some(session);
+                    self.status = Some(Err(GuiError::Model("training stopped unexpectedly".to_string())));
+                },
+                Some(Err(panic)) => {
+                    let reason = panic.downcast_ref::<&str>().map(|reason| reason.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown error".to_string());
+                    self.status = Some(Err(GuiError::Model(format!("training crashed: {}", reason))));
+                },
+                None => (),
+            }
+        }
+        snapshots
+    }
+
+    /// Whether the session was reset or a model loaded since the last call.
+    pub fn take_restarted(&mut self) -> bool {
+        std::mem::take(&mut self.restarted)
+    }
+
+    /// Stops the worker and hands the session back once its current step finishes.
+    pub fn shutdown(self) -> Option<TrainingSession> {
+        let Trainer { commands, events, handle, stopped, .. } = self;
+        let _ = commands.send(TrainerCommand::Shutdown);
+        // A worker waiting on a full event queue gives up once nobody is listening
+        drop(events);
+        stopped.or_else(|| handle.and_then(|handle| handle.join().ok()))
+    }
+}
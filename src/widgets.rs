@@ -0,0 +1,19 @@
+pub mod accuracy;
+pub mod architecture;
+pub mod augment_preview;
+pub mod confusion_matrix;
+pub mod conv_arch;
+pub mod cost_plot;
+pub mod output_graph;
+pub mod output_img;
+pub mod prediction_gallery;
+
+pub use accuracy::Accuracy;
+pub use architecture::Architecture;
+pub use augment_preview::AugmentPreview;
+pub use confusion_matrix::ConfusionMatrix;
+pub use conv_arch::ConvArch;
+pub use cost_plot::CostPlot;
+pub use output_graph::OutputGraph;
+pub use output_img::OutputImg;
+pub use prediction_gallery::{GallerySource, PredictionGallery};
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use graphics::Context;
+use piston_window::*;
+
+use crate::dataset::Dataset;
+use crate::evaluation::ClassAccuracy;
+use crate::history::{Bucket, History};
+use crate::plot::Axes;
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{draw_text, Rect, WidgetView, LINE_THICKNESS, OUTLINE};
+
+const ACCURACY_COLOR: [f32; 4] = [0.0, 0.8, 0.0, 1.0];
+
+/// Argmax accuracy of a classifier over the dataset, plotted over epochs with a per-class breakdown.
+pub struct Accuracy {
+    pub dataset: Option<Arc<dyn Dataset>>,
+    pub labels: Vec<String>,
+    /// Overall accuracy over epochs.
+    pub history: History,
+    pub current: ClassAccuracy
+}
+
+impl Accuracy {
+    pub fn new() -> Self {
+        Accuracy {
+            dataset: None,
+            labels: vec![],
+            history: History::new(),
+            current: ClassAccuracy::default()
+        }
+    }
+
+    fn label(&self, class: usize) -> String {
+        self.labels.get(class).cloned().unwrap_or_else(|| class.to_string())
+    }
+}
+
+impl Default for Accuracy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for Accuracy {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        let dataset = match &self.dataset {
+            Some(dataset) if !snapshot.outputs.is_empty() => dataset,
+            _ => return,
+        };
+        self.current = ClassAccuracy::from_dataset(&snapshot.outputs, dataset.as_ref());
+        if let (true, Some(accuracy)) = (snapshot.trained, self.current.overall()) {
+            self.history.push(snapshot.epochs, accuracy);
+        }
+    }
+
+    fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        self.labels = dataset.label_names().map_or(vec![], |labels| labels.to_vec());
+        self.dataset = Some(dataset);
+        self.history.clear();
+        self.current = ClassAccuracy::default();
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, _window_ctx: &mut G2dTextureContext, glyphs: &mut Glyphs) {
+        let list_width = area[2] * 0.3;
+        let plot_area = [area[0], area[1], area[2] - list_width, area[3]];
+
+        let max_buckets = (plot_area[2] / 2.0).max(2.0) as usize;
+        let history = self.history.view(self.history.first_epoch().unwrap_or(0), max_buckets);
+        let first_epoch = history.first().map_or(0, |bucket| bucket.first_epoch) as f64;
+        let last_epoch = history.last().map_or(0, |bucket| bucket.last_epoch) as f64;
+
+        let axes = Axes::new(plot_area, [first_epoch, last_epoch.max(first_epoch + 1.0)], [0.0, 1.0]);
+        axes.draw(&ctx, gl, glyphs);
+        let point = |bucket: &Bucket| [(bucket.first_epoch + bucket.last_epoch) as f64 / 2.0, bucket.mean()];
+        for pair in history.windows(2) {
+            axes.line(ACCURACY_COLOR, LINE_THICKNESS * 1.5, point(&pair[0]), point(&pair[1]), &ctx, gl);
+        }
+
+        let overall = match self.current.overall() {
+            Some(overall) => overall,
+            None => return,
+        };
+        let text_size = axes.text_size;
+        let x = area[0] + area[2] - list_width + text_size * 0.5;
+        let mut y = area[1] + text_size * 1.5;
+        draw_text(&format!("Accuracy {:.1}%", overall * 100.0), OUTLINE, text_size, [x, y], &ctx, gl, glyphs);
+
+        // Classes that don't fit are summarised on the last line
+        let rows = (((area[3] - text_size * 2.0) / (text_size * 1.3)).floor() as usize).max(1);
+        let classes = self.current.classes();
+        for class in 0..classes {
+            y += text_size * 1.3;
+            if class + 1 == rows && classes > rows {
+                draw_text(&format!("+{} more", classes - class), OUTLINE, text_size, [x, y], &ctx, gl, glyphs);
+                break;
+            }
+            let (text, color) = match self.current.class(class) {
+                Some(accuracy) => (
+                    format!("{}: {:.0}% ({})", self.label(class), accuracy * 100.0, self.current.total[class]),
+                    [1.0 - accuracy as f32, 0.4 + 0.6 * accuracy as f32, 0.2, 1.0]
+                ),
+                None => (format!("{}: -", self.label(class)), OUTLINE),
+            };
+            draw_text(&text, color, text_size, [x, y], &ctx, gl, glyphs);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.current = ClassAccuracy::default();
+    }
+}
@@ -0,0 +1,100 @@
+use graphics::{rectangle::rectangle_by_corners, Context};
+use piston_window::*;
+
+use crate::snapshot::{LayerSnapshot, NetworkSnapshot};
+use crate::widget::{Rect, WidgetView, LINE_THICKNESS};
+
+pub struct Architecture {
+    pub layers: Vec<LayerSnapshot>,
+    pub nodes: Vec<usize>
+}
+
+impl Architecture {
+    pub fn new() -> Self {
+        Architecture {
+            layers: vec![],
+            nodes: vec![]
+        }
+    }
+
+    fn get_max_nodes(nodes: &[usize]) -> usize {
+        nodes.iter().cloned().max().unwrap_or(0)
+    }
+
+    fn sigmoid(x: f64) -> f32 {
+        1.0 / (1.0 + (-x).exp()) as f32
+    }
+}
+
+impl Default for Architecture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for Architecture {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        self.layers = snapshot.dense_layers().cloned().collect();
+        self.nodes = snapshot.dense_nodes();
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, _window_ctx: &mut G2dTextureContext, _glyphs: &mut Glyphs) {
+
+        let layer_nodes = &self.nodes;
+        let layers = &self.layers;
+        let (width, height) = (area[2], area[3]);
+        let padding = [width * 0.05, height * 0.05];
+
+        let mut neuron_color = [0.7, 0.7, 0.0, 1.0];
+        let mut weight_color = [0.7, 0.7, 0.0, 1.0];
+
+        let floor = area[1] + height - (padding[1] * 0.5);
+        let wall = area[0] + (padding[0] * 2.0);
+        let y_center = floor - (height / 2.0);
+        let x_center = wall + (width / 2.0);
+
+        let max_nodes = Self::get_max_nodes(layer_nodes) as f64;
+        let neuron_size =  50.0 * ((((width / ctx.get_view_size()[0]) + (height / ctx.get_view_size()[1])) / 2.0) / max_nodes);
+        let network_width = width +  -(padding[0] * 2.0) + -(neuron_size * 2.0);
+        let layer_width = network_width / layer_nodes.len() as f64;
+
+        for i in 0..layer_nodes.len() { //layers
+            let layer_height =  ((height - (padding[1] * 2.0)) - (layer_nodes[i] as f64 * neuron_size)) * (layer_nodes[i] as f64 / max_nodes);
+
+            let neuron_spacing = layer_height / layer_nodes[i] as f64;
+
+            let x = (x_center - (network_width / 2.0)) + (i as f64 * layer_width);
+
+            for j in 0..layer_nodes[i] { //layer nodes
+                    if i != 0 {
+                        let val = Self::sigmoid(layers[i - 1].biases[j]);
+                        neuron_color[0] = 1.0 - val;
+                        neuron_color[1] = val;
+                        neuron_color[2] = 1.0 - val;
+                    }
+                    let y = (y_center - (layer_height / 2.0)) + (neuron_size * 2.0) + (j as f64 * neuron_spacing);
+                    let rect = rectangle_by_corners(x - neuron_size, y - neuron_size, x + neuron_size, y + neuron_size);
+                    ellipse::Ellipse::new(neuron_color).draw(rect, &ctx.draw_state, ctx.transform, gl);
+
+                if i + 1 != layer_nodes.len() {
+                    let next_layer_height = ((height - (padding[1] * 2.0)) - (layer_nodes[i + 1] as f64 * neuron_size)) * (layer_nodes[i + 1] as f64 / max_nodes);
+                    for k in 0..layer_nodes[i + 1] { //next nodes
+                        let val = Self::sigmoid(layers[i].dense_weights[j][k]);
+                        weight_color[0] = 1.0 - val;
+                        weight_color[1] = val;
+                        weight_color[2] = 1.0 - val;
+                        let next_neuron_spacing = next_layer_height / layer_nodes[i + 1] as f64;
+                        let next_x = (x_center - (network_width / 2.0)) + ((i + 1) as f64 * layer_width);
+                        let next_y = (y_center - (next_layer_height / 2.0)) + (neuron_size * 2.0) + (k as f64 * next_neuron_spacing);
+
+                        line_from_to(weight_color, LINE_THICKNESS, 
+                            [x, y], 
+                            [next_x, next_y], 
+                            ctx.transform, 
+                            gl);
+                    }
+                }
+            }
+        }
+    }
+}
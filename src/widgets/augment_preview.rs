@@ -0,0 +1,57 @@
+use graphics::Context;
+use piston_window::*;
+
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{draw_channels, Rect, WidgetView};
+
+/// Grid of augmented training inputs, redrawn every step so the distortions can be checked while training.
+pub struct AugmentPreview {
+    pub samples: Vec<Vec<Vec<Vec<f64>>>>
+}
+
+impl AugmentPreview {
+    pub fn new() -> Self {
+        AugmentPreview {
+            samples: vec![]
+        }
+    }
+}
+
+impl Default for AugmentPreview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for AugmentPreview {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        self.samples = snapshot.augmented.clone();
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, window_ctx: &mut G2dTextureContext, _glyphs: &mut Glyphs) {
+        let samples: Vec<&Vec<Vec<Vec<f64>>>> = self.samples.iter()
+            .filter(|sample| !sample.is_empty() && !sample[0].is_empty() && !sample[0][0].is_empty())
+            .collect();
+        if samples.is_empty() {
+            return;
+        }
+
+        let padding = [area[2] * 0.05, area[3] * 0.05];
+        let columns = (samples.len() as f64).sqrt().ceil() as usize;
+        let rows = samples.len().div_ceil(columns);
+        let cell = ((area[2] - padding[0] * 2.0) / columns as f64).min((area[3] - padding[1] * 2.0) / rows as f64);
+        let x = area[0] + (area[2] - cell * columns as f64) / 2.0;
+        let y = area[1] + (area[3] - cell * rows as f64) / 2.0;
+
+        for (i, sample) in samples.iter().enumerate() {
+            let size = cell * 0.9;
+            let cell_x = x + (i % columns) as f64 * cell + (cell - size) / 2.0;
+            let cell_y = y + (i / columns) as f64 * cell + (cell - size) / 2.0;
+            draw_channels(sample, [cell_x, cell_y, size, size], &ctx, gl, window_ctx);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.samples = vec![];
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use graphics::{rectangle, Context};
+use piston_window::*;
+
+use crate::dataset::Dataset;
+use crate::evaluation::Confusion;
+use crate::plot::label_width;
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{draw_text, Rect, WidgetView, LINE_THICKNESS, OUTLINE};
+
+/// Colour-coded counts of predicted (columns) against target (rows) classes.
+/// `N` switches between raw counts and rows normalised to the fraction of each target class.
+pub struct ConfusionMatrix {
+    pub dataset: Option<Arc<dyn Dataset>>,
+    pub labels: Vec<String>,
+    pub confusion: Confusion,
+    pub normalized: bool
+}
+
+impl ConfusionMatrix {
+    pub fn new() -> Self {
+        ConfusionMatrix {
+            dataset: None,
+            labels: vec![],
+            confusion: Confusion::default(),
+            normalized: false
+        }
+    }
+
+    pub fn with_normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    fn label(&self, class: usize) -> String {
+        self.labels.get(class).cloned().unwrap_or_else(|| class.to_string())
+    }
+}
+
+impl Default for ConfusionMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for ConfusionMatrix {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        if let (Some(dataset), false) = (&self.dataset, snapshot.outputs.is_empty()) {
+            self.confusion = Confusion::from_dataset(&snapshot.outputs, dataset.as_ref());
+        }
+    }
+
+    fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        self.labels = dataset.label_names().map_or(vec![], |labels| labels.to_vec());
+        self.dataset = Some(dataset);
+        self.reset();
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, _window_ctx: &mut G2dTextureContext, glyphs: &mut Glyphs) {
+        let classes = self.confusion.classes();
+        if classes == 0 {
+            return;
+        }
+
+        let text_size = (area[3] * 0.05).clamp(8.0, 14.0);
+        let widest = (0..classes).map(|class| label_width(&self.label(class), text_size)).fold(0.0, f64::max);
+        let margin = [widest + text_size * 1.5, text_size * 3.0];
+        let cell = ((area[2] - margin[0] - text_size) / classes as f64).min((area[3] - margin[1] - text_size) / classes as f64);
+        if cell <= 0.0 {
+            return;
+        }
+        let origin = [area[0] + margin[0], area[1] + margin[1]];
+
+        draw_text("Predicted", OUTLINE, text_size, [origin[0], area[1] + text_size * 1.2], &ctx, gl, glyphs);
+        let fractions = self.confusion.row_normalized();
+        let cell_text = (cell * 0.35).min(text_size);
+
+        for (target, row) in fractions.iter().enumerate() {
+            let y = origin[1] + target as f64 * cell;
+            let label = self.label(target);
+            let position = [origin[0] - label_width(&label, text_size) - text_size * 0.5, y + cell / 2.0 + text_size * 0.35];
+            draw_text(&label, OUTLINE, text_size, position, &ctx, gl, glyphs);
+
+            for (predicted, fraction) in row.iter().enumerate() {
+                let x = origin[0] + predicted as f64 * cell;
+                let fraction = *fraction as f32;
+                // Correct predictions shade green, mistakes red, both by how much of the row they take
+                let color = match target == predicted {
+                    true => [0.1, 0.2 + 0.7 * fraction, 0.1, 1.0],
+                    false => [0.2 + 0.7 * fraction, 0.1, 0.1, 1.0],
+                };
+                rectangle(color, [x, y, cell, cell], ctx.transform, gl);
+                rectangle::Rectangle::new_border(OUTLINE, LINE_THICKNESS * 0.5)
+                    .draw([x, y, cell, cell], &ctx.draw_state, ctx.transform, gl);
+
+                if cell_text >= 6.0 {
+                    let text = match self.normalized {
+                        true => format!("{:.0}%", fraction * 100.0),
+                        false => self.confusion.counts[target][predicted].to_string(),
+                    };
+                    let position = [x + (cell - label_width(&text, cell_text)) / 2.0, y + cell / 2.0 + cell_text * 0.35];
+                    draw_text(&text, [1.0, 1.0, 1.0, 1.0], cell_text, position, &ctx, gl, glyphs);
+                }
+            }
+        }
+
+        for predicted in 0..classes {
+            let label = self.label(predicted);
+            let x = origin[0] + predicted as f64 * cell + (cell - label_width(&label, text_size)) / 2.0;
+            draw_text(&label, OUTLINE, text_size, [x, origin[1] - text_size * 0.4], &ctx, gl, glyphs);
+        }
+    }
+
+    fn handle_input(&mut self, button: &Button) -> bool {
+        let used = matches!(button, Button::Keyboard(Key::N));
+        if used {
+            self.normalized = !self.normalized;
+        }
+        used
+    }
+
+    fn reset(&mut self) {
+        self.confusion = Confusion::default();
+    }
+}
@@ -0,0 +1,158 @@
+extern crate image;
+
+use std::sync::Arc;
+
+use graphics::Context;
+use image::{ImageBuffer, Rgba};
+use piston_window::*;
+
+use crate::dataset::{Dataset, Input};
+use crate::snapshot::{LayerSnapshot, NetworkSnapshot};
+use crate::widget::{Rect, WidgetView};
+
+pub struct ConvArch {
+    pub layers: Vec<LayerSnapshot>,
+    /// First sample of the dataset, drawn as the network input.
+    pub input: Vec<Vec<Vec<f64>>>
+}
+
+impl ConvArch {
+    pub fn new() -> Self {
+        ConvArch {
+            layers: vec![],
+            input: vec![]
+        }
+    }
+}
+
+impl Default for ConvArch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for ConvArch {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        self.layers = snapshot.conv_layers().cloned().collect();
+    }
+
+    fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        self.input = match dataset.is_empty() {
+            true => vec![],
+            false => match dataset.get(0).input {
+                Input::Conv(channels) => channels,
+                Input::Dense(_) => vec![],
+            },
+        };
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, window_ctx: &mut G2dTextureContext, _glyphs: &mut Glyphs) {
+
+        if self.input.is_empty() || self.layers.is_empty() {
+            return;
+        }
+
+        let layers = &self.layers;
+        let img = &self.input;
+        let layer_count = layers.len();
+        let channels = img.len();
+        let (width, height) = (area[2], area[3]);
+        let padding = [width * 0.05, height * 0.05];
+
+        let floor = area[1] + height - (padding[1] * 0.5);
+        let wall = area[0] + (padding[0] * 2.0);
+        let y_center = floor - (height / 2.0);
+        let x_center = wall + (width / 2.0);
+
+        let network_width = width + -(padding[0] * 2.0);
+        let network_height = height + -(padding[1] * 2.0);
+        let layer_width = network_width / (layer_count as f64 * 2.0 + 1.0);
+        let layer_height = network_height / channels as f64;
+
+        let neuron_size =  100.0 * ((((width / ctx.get_view_size()[0]) + (height / ctx.get_view_size()[1])) / 2.0) / channels as f64);
+
+        for i in 0..channels { 
+            let inp_x = (x_center - (network_width / 2.0)) - neuron_size;
+            let y = (y_center - network_height / 2.0) + (i as f64 * layer_height) + neuron_size * 1.5;
+
+            let w = img[0][0].len();
+            let h = img[0].len();
+            let input_image = ImageBuffer::from_fn(w as u32, h as u32, |x, y| {
+                let pix = (img[i][y as usize][x as usize] * 255.0) as u8;
+                Rgba([pix, pix, pix, 255]) // Varying colors in a gradient
+            });
+
+            let output_texture = match piston_window::Texture::from_image(
+                window_ctx,
+                &input_image,
+                &TextureSettings::new(),
+            ) {
+                Ok(texture) => texture,
+                Err(_) => return,
+            };
+
+            let w_scale = neuron_size / w as f64;
+            let h_scale = neuron_size / h as f64;
+
+            piston_window::image(&output_texture, ctx.transform.trans(inp_x, y).scale(w_scale, h_scale), gl);
+
+            for (j, layer) in layers.iter().enumerate() { //layers
+
+                let x = (x_center - (network_width / 2.0)) + (j as f64 * (layer_width * 2.0)) + layer_width - neuron_size;
+                let next_x = (x_center - (network_width / 2.0)) + layer_width + (j as f64 * (layer_width * 2.0)) + layer_width - neuron_size;
+                
+                if i < layer.conv_kernels.len() && !layer.conv_kernels[i].is_empty() {
+                    let kernel = &layer.conv_kernels[i];
+                    let w = kernel[0].len();
+                    let h = kernel.len();
+
+                    let weight_image = ImageBuffer::from_fn(w as u32, h as u32, |x, y| {
+                        let pix = (kernel[y as usize][x as usize] * 255.0) as u8;
+                        Rgba([255 - pix, pix, 255 - pix, 255]) // Varying colors in a gradient
+                    });
+
+                    let weight_texture = match piston_window::Texture::from_image(
+                        window_ctx,
+                        &weight_image,
+                        &TextureSettings::new(),
+                    ) {
+                        Ok(texture) => texture,
+                        Err(_) => return,
+                    };
+
+                    let w_scale = neuron_size / w as f64;
+                    let h_scale = neuron_size / h as f64;
+
+                    piston_window::image(&weight_texture, ctx.transform.trans(x, y).scale(w_scale, h_scale), gl);
+                }
+                
+                if layer.feature_maps.is_empty() {
+                    continue;
+                }
+                let output = &layer.feature_maps[0];
+
+                let w = output[0].len();
+                let h = output.len();
+
+                let output_image = ImageBuffer::from_fn(w as u32, h as u32, |x, y| {
+                    let pix = (output[y as usize][x as usize] * 255.0) as u8;
+                    Rgba([pix, pix, pix, 255]) // Varying colors in a gradient
+                });
+
+                let output_image = match piston_window::Texture::from_image(
+                    window_ctx,
+                    &output_image,
+                    &TextureSettings::new(),
+                ) {
+                    Ok(texture) => texture,
+                    Err(_) => return,
+                };
+
+                let w_scale = neuron_size / w as f64;
+                let h_scale = neuron_size / h as f64;
+
+                piston_window::image(&output_image, ctx.transform.trans(next_x, y).scale(w_scale, h_scale), gl);
+            }
+        }
+    }
+}
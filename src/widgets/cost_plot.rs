@@ -0,0 +1,237 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use graphics::Context;
+use piston_window::*;
+
+use crate::error::GuiError;
+use crate::history::{Bucket, History};
+use crate::plot::{data_range, Axes};
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{draw_text, Rect, WidgetView, LINE_THICKNESS, OUTLINE};
+
+const TRAINING_COLOR: [f32; 4] = [0.0, 0.8, 0.0, 1.0];
+const SMOOTHED_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 1.0];
+const VALIDATION_COLOR: [f32; 4] = [0.3, 0.6, 1.0, 1.0];
+
+/// Training and validation cost over epochs. `G` toggles a log scale y-axis,
+/// `Backspace` switches between the whole run and the last `window` epochs.
+///
+/// Costs are kept in a bounded `History` and drawn as a min/max envelope around the mean,
+/// so overnight runs render as fast as short ones.
+pub struct CostPlot {
+    pub cost: History,
+    /// Exponential moving average of the cost, recorded as it arrives.
+    pub smoothed: History,
+    pub validation: History,
+    pub log_scale: bool,
+    /// Weight of the previous value in the exponential moving average drawn over the cost, `0.0` turns it off.
+    pub smoothing: f64,
+    /// Epochs shown by the rolling view.
+    pub window: usize,
+    pub rolling: bool,
+    /// Every cost is also appended to this CSV file as `epoch,cost,validation_cost`.
+    pub log_path: Option<String>,
+    log: Option<BufWriter<File>>,
+    /// Why logging stopped, until the window shows it.
+    log_error: Option<GuiError>,
+    average: Option<f64>
+}
+
+impl CostPlot {
+    pub fn new() -> Self {
+        CostPlot {
+            cost: History::new(),
+            smoothed: History::new(),
+            validation: History::new(),
+            log_scale: false,
+            smoothing: 0.9,
+            window: 1000,
+            rolling: false,
+            log_path: None,
+            log: None,
+            log_error: None,
+            average: None
+        }
+    }
+
+    pub fn with_log_scale(mut self, log_scale: bool) -> Self {
+        self.log_scale = log_scale;
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 0.999);
+        self
+    }
+
+    /// Starts in the rolling view showing only the last `epochs` epochs.
+    pub fn with_window(mut self, epochs: usize) -> Self {
+        self.window = epochs.max(1);
+        self.rolling = true;
+        self
+    }
+
+    /// Keeps the full cost history on disk, the file is recreated whenever training restarts.
+    pub fn with_log(mut self, path: &str) -> Self {
+        self.log_path = Some(path.to_string());
+        self
+    }
+
+    /// Appends the snapshot's costs to the log, which is given up on the first error.
+    fn write_log(&mut self, snapshot: &NetworkSnapshot) {
+        let path = match &self.log_path {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        let validation = match (snapshot.validated, snapshot.validation_cost) {
+            (true, Some(cost)) => cost.to_string(),
+            _ => String::new(),
+        };
+        let written = match self.log.as_mut() {
+            Some(log) => writeln!(log, "{},{},{}", snapshot.epochs, snapshot.cost, validation),
+            None => File::create(&path).and_then(|file| {
+                let mut log = BufWriter::new(file);
+                writeln!(log, "epoch,cost,validation_cost")?;
+                writeln!(log, "{},{},{}", snapshot.epochs, snapshot.cost, validation)?;
+                self.log = Some(log);
+                Ok(())
+            }),
+        };
+        if let Err(e) = written {
+            self.log_error = Some(GuiError::io(&path, e));
+            self.log_path = None;
+            self.log = None;
+        }
+    }
+
+    /// First epoch of the current view.
+    fn view_start(&self) -> usize {
+        let last = self.cost.last().map_or(0, |bucket| bucket.last_epoch);
+        match self.rolling {
+            true => last.saturating_sub(self.window),
+            false => self.cost.first_epoch().unwrap_or(0),
+        }
+    }
+}
+
+impl Default for CostPlot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for CostPlot {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        // Snapshots after commands repeat the last epoch
+        if !snapshot.trained {
+            return;
+        }
+        self.cost.push(snapshot.epochs, snapshot.cost);
+        let average = match self.average {
+            Some(average) => self.smoothing * average + (1.0 - self.smoothing) * snapshot.cost,
+            None => snapshot.cost,
+        };
+        self.average = Some(average);
+        self.smoothed.push(snapshot.epochs, average);
+        if let (true, Some(cost)) = (snapshot.validated, snapshot.validation_cost) {
+            self.validation.push(snapshot.epochs, cost);
+        }
+        self.write_log(snapshot);
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, _window_ctx: &mut G2dTextureContext, glyphs: &mut Glyphs) {
+        let from = self.view_start();
+        // About one bucket per two pixels is as much detail as can be seen
+        let max_buckets = (area[2] / 2.0).max(2.0) as usize;
+        let cost = self.cost.view(from, max_buckets);
+        let validation = self.validation.view(from, max_buckets);
+
+        let first_epoch = cost.first().map_or(from, |bucket| bucket.first_epoch) as f64;
+        let last_epoch = cost.last().map_or(from, |bucket| bucket.last_epoch) as f64;
+        let x_range = [first_epoch, last_epoch.max(first_epoch + 1.0)];
+
+        let values = cost.iter().chain(validation.iter()).flat_map(|bucket| [bucket.min, bucket.max]);
+        let y_range = match self.log_scale {
+            true => data_range(values.filter(|cost| *cost > 0.0), 0.0).unwrap_or([0.001, 1.0]),
+            false => [0.0, data_range(values, 0.0).map_or(1.0, |[_, max]| max * 1.05)],
+        };
+
+        let axes = Axes::new(area, x_range, y_range).with_log_y(self.log_scale);
+        axes.draw(&ctx, gl, glyphs);
+
+        if cost.is_empty() {
+            return;
+        }
+
+        let max_cost = cost.iter().map(|bucket| bucket.max).fold(f64::NEG_INFINITY, f64::max);
+        let smoothed = self.smoothing > 0.0 && cost.len() > 1;
+        let alpha = if smoothed { 0.4 } else { 1.0 };
+        for (i, bucket) in cost.iter().enumerate() {
+            let ratio = (bucket.mean() / max_cost) as f32;
+            let line_color: [f32; 4] = [ratio, 1.0 - ratio, 0.0, alpha];
+            // Buckets summarising several costs show their spread as a vertical bar
+            if bucket.count > 1 && bucket.max > bucket.min {
+                let x = center(bucket);
+                axes.line([line_color[0], line_color[1], 0.0, alpha * 0.5], LINE_THICKNESS, [x, bucket.min], [x, bucket.max], &ctx, gl);
+            }
+            if i > 0 {
+                axes.line(line_color, LINE_THICKNESS * 1.5, point(&cost[i - 1]), point(bucket), &ctx, gl);
+            }
+        }
+
+        let mut entries = vec![(TRAINING_COLOR, "Training")];
+
+        if smoothed {
+            let average = self.smoothed.view(from, max_buckets);
+            for pair in average.windows(2) {
+                axes.line(SMOOTHED_COLOR, LINE_THICKNESS * 2.0, point(&pair[0]), point(&pair[1]), &ctx, gl);
+            }
+            entries.push((SMOOTHED_COLOR, "Smoothed"));
+        }
+
+        for pair in validation.windows(2) {
+            axes.line(VALIDATION_COLOR, LINE_THICKNESS * 1.5, point(&pair[0]), point(&pair[1]), &ctx, gl);
+        }
+        if !validation.is_empty() {
+            entries.push((VALIDATION_COLOR, "Validation"));
+        }
+
+        axes.legend(&entries, &ctx, gl, glyphs);
+
+        if self.rolling {
+            let position = [axes.plot[0] + axes.text_size * 0.5, axes.plot[1] + axes.text_size * 1.2];
+            draw_text(&format!("Last {} epochs", self.window), OUTLINE, axes.text_size, position, &ctx, gl, glyphs);
+        }
+    }
+
+    fn take_error(&mut self) -> Option<GuiError> {
+        self.log_error.take()
+    }
+
+    fn handle_input(&mut self, button: &Button) -> bool {
+        match button {
+            Button::Keyboard(Key::Backspace) => self.rolling = !self.rolling,
+            Button::Keyboard(Key::G) => self.log_scale = !self.log_scale,
+            _ => return false,
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.cost.clear();
+        self.smoothed.clear();
+        self.validation.clear();
+        self.average = None;
+        // Reopened on the next update so the log only holds the new run
+        self.log = None;
+    }
+}
+
+fn center(bucket: &Bucket) -> f64 {
+    (bucket.first_epoch + bucket.last_epoch) as f64 / 2.0
+}
+
+fn point(bucket: &Bucket) -> [f64; 2] {
+    [center(bucket), bucket.mean()]
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use graphics::Context;
+use piston_window::*;
+
+use crate::dataset::Dataset;
+use crate::plot::{data_range, Axes};
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{Rect, WidgetView, LINE_THICKNESS};
+
+const TARGET_COLOR: [f32; 4] = [0.0, 0.8, 0.0, 1.0];
+const NETWORK_COLOR: [f32; 4] = [0.2, 0.4, 1.0, 1.0];
+
+/// Plots the first network output against the first input, next to the expected targets.
+/// The x-axis spans the dataset's `input_ranges`, e.g. those of a `Function`, or the inputs themselves,
+/// and the y-axis fits both targets and outputs unless a range is configured.
+pub struct OutputGraph {
+    /// First input value of every sample.
+    pub inputs: Vec<f64>,
+    /// First target value of every sample, the only one drawn.
+    pub targets: Vec<f64>,
+    /// Sample indices sorted by input so randomly sampled data is drawn left to right.
+    pub order: Vec<usize>,
+    pub x_range: [f64; 2],
+    pub y_range: [f64; 2],
+    /// Ranges set with `with_x_range` and `with_y_range`, kept when the data changes.
+    pub fixed_x_range: Option<[f64; 2]>,
+    pub fixed_y_range: Option<[f64; 2]>,
+    pub nn_data: Vec<Vec<f64>>
+}
+
+impl OutputGraph {
+    pub fn new() -> Self {
+        OutputGraph {
+            inputs: vec![],
+            targets: vec![],
+            order: vec![],
+            x_range: [-1.0, 1.0],
+            y_range: [-1.0, 1.0],
+            fixed_x_range: None,
+            fixed_y_range: None,
+            nn_data: vec![]
+        }
+    }
+
+    pub fn with_x_range(mut self, min: f64, max: f64) -> Self {
+        self.fixed_x_range = Some([min, max]);
+        self.x_range = [min, max];
+        self
+    }
+
+    pub fn with_y_range(mut self, min: f64, max: f64) -> Self {
+        self.fixed_y_range = Some([min, max]);
+        self.y_range = [min, max];
+        self
+    }
+
+    fn fit_y_range(&mut self) {
+        if let Some(range) = self.fixed_y_range {
+            self.y_range = range;
+            return;
+        }
+        let values = self.targets.iter().cloned().chain(self.nn_data.iter().filter_map(|values| values.first().cloned()));
+        self.y_range = data_range(values, 0.05).unwrap_or([-1.0, 1.0]);
+    }
+}
+
+impl Default for OutputGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for OutputGraph {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        if snapshot.outputs.is_empty() {
+            return;
+        }
+        self.nn_data = snapshot.outputs.clone();
+        self.fit_y_range();
+    }
+
+    fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        let first = |values: Vec<f64>| values.first().cloned().unwrap_or(0.0);
+        self.inputs = (0..dataset.len()).map(|i| first(dataset.get(i).input.flatten())).collect();
+        self.targets = (0..dataset.len()).map(|i| first(dataset.target(i))).collect();
+
+        self.order = (0..self.inputs.len()).collect();
+        self.order.sort_by(|&a, &b| self.inputs[a].total_cmp(&self.inputs[b]));
+
+        self.x_range = match (self.fixed_x_range, dataset.input_ranges().and_then(|ranges| ranges.first().cloned())) {
+            (Some(range), _) => range,
+            (None, Some([min, max])) if max > min => [min, max],
+            _ => data_range(self.inputs.iter().cloned(), 0.0).unwrap_or([-1.0, 1.0]),
+        };
+        self.nn_data = vec![];
+        self.fit_y_range();
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, _window_ctx: &mut G2dTextureContext, glyphs: &mut Glyphs) {
+        let axes = Axes::new(area, self.x_range, self.y_range);
+        axes.draw(&ctx, gl, glyphs);
+
+        if self.targets.is_empty() {
+            return;
+        }
+
+        let network: Vec<Option<f64>> = self.nn_data.iter().map(|values| values.first().cloned()).collect();
+        let targets: Vec<Option<f64>> = self.targets.iter().map(|value| Some(*value)).collect();
+        for (values, color) in [(targets, TARGET_COLOR), (network, NETWORK_COLOR)] {
+            let mut last_point: Option<[f64; 2]> = None;
+            for &index in self.order.iter() {
+                let value = match values.get(index).cloned().flatten() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let point = [self.inputs[index], value];
+                if let Some(last) = last_point {
+                    axes.line(color, LINE_THICKNESS * 1.5, last, point, &ctx, gl);
+                }
+                last_point = Some(point);
+            }
+        }
+
+        axes.legend(&[(TARGET_COLOR, "Target"), (NETWORK_COLOR, "Network")], &ctx, gl, glyphs);
+    }
+}
@@ -0,0 +1,111 @@
+extern crate image;
+
+use std::sync::Arc;
+
+use graphics::Context;
+use image::{ImageBuffer, Rgba};
+use piston_window::*;
+
+use crate::dataset::Dataset;
+use crate::export::Colormap;
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{Rect, WidgetView};
+
+/// Target image of a coordinate network next to what the network currently draws.
+pub struct OutputImg {
+    pub dataset: Option<Arc<dyn Dataset>>,
+    pub nn_data: Vec<Vec<f64>>,
+    /// `[width, height]` of the dataset's image.
+    pub size: [usize; 2]
+}
+
+impl OutputImg {
+    pub fn new() -> Self {
+        OutputImg {
+            dataset: None,
+            nn_data: vec![],
+            size: [0, 0]
+        }
+    }
+
+    /// One pixel per sample in row order, gray or RGB depending on the number of outputs.
+    fn to_image<F: Fn(usize) -> Option<Vec<f64>>>(pixel: F, size: [usize; 2]) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(size[0] as u32, size[1] as u32, |x, y| {
+            let index = y as usize * size[0] + x as usize;
+            let [r, g, b] = pixel(index).map_or([0, 0, 0], |pixel| Colormap::Gray.pixel(&pixel).0);
+            Rgba([r, g, b, 255])
+        })
+    }
+}
+
+impl Default for OutputImg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetView for OutputImg {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        if !snapshot.outputs.is_empty() {
+            self.nn_data = snapshot.outputs.clone();
+        }
+    }
+
+    fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        // Datasets that aren't coordinate images are drawn as a square if their length allows it
+        let side = (dataset.len() as f64).sqrt() as usize;
+        self.size = match dataset.image_size() {
+            Some(size) => size,
+            None if side * side == dataset.len() => [side, side],
+            None => [0, 0],
+        };
+        self.dataset = Some(dataset);
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, window_ctx: &mut G2dTextureContext, _glyphs: &mut Glyphs) {
+
+        let dataset = match &self.dataset {
+            Some(dataset) if !dataset.is_empty() => dataset.clone(),
+            _ => return,
+        };
+        if self.nn_data.is_empty() || self.size[0] * self.size[1] == 0 {
+            return;
+        }
+
+        let padding = [area[2] * 0.05, area[3] * 0.05];
+        let (w, h) = (self.size[0], self.size[1]);
+
+        let base_image = Self::to_image(|index| (index < dataset.len()).then(|| dataset.target(index)), self.size);
+        let output_image = Self::to_image(|index| self.nn_data.get(index).cloned(), self.size);
+
+        // Create a texture from the network outputs
+        let output_texture = match piston_window::Texture::from_image(
+            window_ctx,
+            &output_image,
+            &TextureSettings::new(),
+        ) {
+            Ok(texture) => texture,
+            Err(_) => return,
+        };
+
+        // Create a texture from the dataset targets
+        let base_texture = match piston_window::Texture::from_image(
+            window_ctx,
+            &base_image,
+            &TextureSettings::new(),
+        ) {
+            Ok(texture) => texture,
+            Err(_) => return,
+        };
+
+        // Keep the aspect ratio of the image, two images side by side
+        let scale = (((area[2] - padding[0] * 2.0) / 2.0) / w as f64).min((area[3] - padding[1] * 2.0) / h as f64);
+        let (w_scale, h_scale) = (scale, scale);
+
+        let x = area[0] + (area[2] / 2.0) - (w_scale * w as f64);
+        let y = (area[1] + (area[3] / 2.0)) - (h_scale * h as f64) / 2.0;
+
+        piston_window::image(&output_texture, ctx.transform.trans(x + (w_scale * w as f64), y).scale(w_scale, h_scale), gl);
+        piston_window::image(&base_texture, ctx.transform.trans(x, y).scale(w_scale, h_scale), gl);
+    }
+}
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use graphics::{rectangle, Context};
+use piston_window::*;
+use serde::Deserialize;
+
+use crate::dataset::{Dataset, Input};
+use crate::evaluation;
+use crate::snapshot::NetworkSnapshot;
+use crate::widget::{draw_channels, draw_text, Rect, WidgetView, OUTLINE};
+
+const CORRECT_COLOR: [f32; 4] = [0.0, 0.8, 0.0, 1.0];
+const WRONG_COLOR: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+
+/// Which samples a `PredictionGallery` shows.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GallerySource {
+    Training,
+    /// Refreshed whenever the validation set is evaluated.
+    Validation
+}
+
+/// One sample as the network currently sees it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Prediction {
+    pub index: usize,
+    pub predicted: usize,
+    pub expected: usize,
+    /// Network output for the predicted class.
+    pub confidence: f64
+}
+
+impl Prediction {
+    pub fn is_correct(&self) -> bool {
+        self.predicted == self.expected
+    }
+}
+
+/// Grid of input images annotated with the predicted label and its confidence, bordered green when correct and red when wrong.
+/// `Left` and `Right` page through the samples, `W` sorts the most confidently wrong predictions first.
+pub struct PredictionGallery {
+    pub source: GallerySource,
+    pub dataset: Option<Arc<dyn Dataset>>,
+    pub labels: Vec<String>,
+    pub predictions: Vec<Prediction>,
+    pub per_page: usize,
+    pub page: usize,
+    pub wrong_first: bool
+}
+
+impl PredictionGallery {
+    pub fn new() -> Self {
+        PredictionGallery {
+            source: GallerySource::Training,
+            dataset: None,
+            labels: vec![],
+            predictions: vec![],
+            per_page: 16,
+            page: 0,
+            wrong_first: false
+        }
+    }
+
+    pub fn with_source(mut self, source: GallerySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_per_page(mut self, samples: usize) -> Self {
+        self.per_page = samples.max(1);
+        self
+    }
+
+    pub fn with_wrong_first(mut self, wrong_first: bool) -> Self {
+        self.wrong_first = wrong_first;
+        self
+    }
+
+    fn pages(&self) -> usize {
+        self.predictions.len().div_ceil(self.per_page).max(1)
+    }
+
+    fn label(&self, class: usize) -> String {
+        self.labels.get(class).cloned().unwrap_or_else(|| class.to_string())
+    }
+
+    /// Wrong predictions from most to least confident, then correct ones from least to most confident.
+    fn sort(&mut self) {
+        if self.wrong_first {
+            self.predictions.sort_by(|a, b| match (a.is_correct(), b.is_correct()) {
+                (false, false) => b.confidence.total_cmp(&a.confidence),
+                (true, true) => a.confidence.total_cmp(&b.confidence),
+                (correct, _) => correct.cmp(&!correct),
+            });
+        } else {
+            self.predictions.sort_by_key(|prediction| prediction.index);
+        }
+    }
+
+    fn set_source(&mut self, dataset: Arc<dyn Dataset>) {
+        self.labels = dataset.label_names().map_or(vec![], |labels| labels.to_vec());
+        self.dataset = Some(dataset);
+        self.predictions = vec![];
+        self.page = 0;
+    }
+}
+
+impl Default for PredictionGallery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The image of a sample input, dense inputs of a square length are shown as one square channel.
+fn image(input: Input) -> Option<Vec<Vec<Vec<f64>>>> {
+    match input {
+        Input::Conv(channels) => Some(channels),
+        Input::Dense(values) => {
+            let side = (values.len() as f64).sqrt().round() as usize;
+            (side > 0 && side * side == values.len()).then(|| vec![values.chunks(side).map(|row| row.to_vec()).collect()])
+        },
+    }
+}
+
+impl WidgetView for PredictionGallery {
+    fn update(&mut self, snapshot: &NetworkSnapshot) {
+        let outputs = match self.source {
+            GallerySource::Training => &snapshot.outputs,
+            GallerySource::Validation => &snapshot.validation_outputs,
+        };
+        let dataset = match &self.dataset {
+            Some(dataset) if !outputs.is_empty() => dataset,
+            _ => return,
+        };
+
+        self.predictions = outputs.iter().enumerate().take(dataset.len())
+            .filter_map(|(index, output)| {
+                let (predicted, expected) = (evaluation::class(output)?, evaluation::class(&dataset.target(index))?);
+                let confidence = match output.as_slice() {
+                    [value] if predicted == 0 => 1.0 - value,
+                    [value] => *value,
+                    values => values[predicted],
+                };
+                Some(Prediction { index, predicted, expected, confidence })
+            })
+            .collect();
+        self.sort();
+        self.page = self.page.min(self.pages() - 1);
+    }
+
+    fn set_dataset(&mut self, dataset: Arc<dyn Dataset>) {
+        if self.source == GallerySource::Training {
+            self.set_source(dataset);
+        }
+    }
+
+    fn set_validation(&mut self, dataset: Arc<dyn Dataset>) {
+        if self.source == GallerySource::Validation {
+            self.set_source(dataset);
+        }
+    }
+
+    fn render(&mut self, area: Rect, ctx: Context, gl: &mut G2d, window_ctx: &mut G2dTextureContext, glyphs: &mut Glyphs) {
+        let dataset = match &self.dataset {
+            Some(dataset) => dataset.clone(),
+            None => return,
+        };
+        let text_size = (area[3] * 0.04).clamp(8.0, 14.0);
+
+        let header = format!(
+            "Page {}/{}{}",
+            self.page + 1,
+            self.pages(),
+            if self.wrong_first { "  Most confidently wrong first" } else { "" }
+        );
+        draw_text(&header, OUTLINE, text_size, [area[0] + text_size * 0.5, area[1] + text_size * 1.3], &ctx, gl, glyphs);
+
+        let start = self.page * self.per_page;
+        let page: Vec<Prediction> = self.predictions.iter().skip(start).take(self.per_page).cloned().collect();
+        if page.is_empty() {
+            return;
+        }
+
+        let top = area[1] + text_size * 2.0;
+        let columns = (self.per_page as f64).sqrt().ceil() as usize;
+        let rows = self.per_page.div_ceil(columns);
+        let cell = (area[2] / columns as f64).min((area[3] - text_size * 2.0) / rows as f64);
+        let x = area[0] + (area[2] - cell * columns as f64) / 2.0;
+        let label_size = (cell * 0.12).min(text_size);
+
+        for (i, prediction) in page.iter().enumerate() {
+            let cell_x = x + (i % columns) as f64 * cell;
+            let cell_y = top + (i / columns) as f64 * cell;
+            let margin = cell * 0.05;
+            let border = [cell_x + margin, cell_y + margin, cell - margin * 2.0, cell - margin * 2.0 - label_size * 1.4];
+
+            let color = if prediction.is_correct() { CORRECT_COLOR } else { WRONG_COLOR };
+            if let Some(channels) = image(dataset.get(prediction.index).input) {
+                let inset = margin * 0.6;
+                draw_channels(&channels, [border[0] + inset, border[1] + inset, border[2] - inset * 2.0, border[3] - inset * 2.0], &ctx, gl, window_ctx);
+            }
+            rectangle::Rectangle::new_border(color, (cell * 0.015).max(1.0))
+                .draw(border, &ctx.draw_state, ctx.transform, gl);
+
+            let text = format!("{} {:.0}%", self.label(prediction.predicted), prediction.confidence * 100.0);
+            draw_text(&text, color, label_size, [border[0], border[1] + border[3] + label_size * 1.2], &ctx, gl, glyphs);
+        }
+    }
+
+    fn handle_input(&mut self, button: &Button) -> bool {
+        match button {
+            Button::Keyboard(Key::Right) => self.page = (self.page + 1) % self.pages(),
+            Button::Keyboard(Key::Left) => self.page = (self.page + self.pages() - 1) % self.pages(),
+            Button::Keyboard(Key::W) => {
+                self.wrong_first = !self.wrong_first;
+                self.sort();
+                self.page = 0;
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.predictions = vec![];
+        self.page = 0;
+    }
+}